@@ -0,0 +1,169 @@
+use crate::identity::Platform;
+use crate::util::{keccak_bytes, verify_ed25519};
+use crate::E8004;
+use anchor_lang::prelude::*;
+
+/// `kind` values of attestation keys and reports.
+pub const ATTESTATION_SGX: u8 = 1;
+pub const ATTESTATION_NITRO: u8 = 2;
+/// Oldest report, in seconds, a response can be attested with.
+pub const MAX_ATTESTATION_AGE: i64 = 3_600;
+
+/// An ed25519 key allowlisted by the platform to sign TEE attestation
+/// reports, at `[b"attkey", key]`.
+#[account]
+pub struct AttestationKey {
+    pub key: Pubkey,
+    pub kind: u8,
+    /// Enclave measurement reports must carry; zero accepts any.
+    pub measurement: [u8; 32],
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+/// Simplified SGX/Nitro-style report. `report_data` must be
+/// `keccak(request_hash || response_hash)`, binding the response to both the
+/// request and the enclave. `timestamp` must fall between the request and
+/// the response, at most `MAX_ATTESTATION_AGE` before the latter.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AttestationReport {
+    pub kind: u8,
+    pub measurement: [u8; 32],
+    pub report_data: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// A report with the attestation key's ed25519 signature over
+/// `keccak(borsh(report))`, verified through an ed25519 instruction in the
+/// same transaction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Attestation {
+    pub report: AttestationReport,
+    pub signature: Vec<u8>,
+}
+
+/// Checks `attestation` against the allowlisted `key` and the response it is
+/// meant to back, recorded at `now` for a request opened at `requested_at`.
+pub(crate) fn verify_attestation(
+    attestation: &Attestation,
+    key: &AttestationKey,
+    request_hash: &[u8; 32],
+    requested_at: i64,
+    response_hash: &[u8; 32],
+    now: i64,
+    ix_sysvar: &AccountInfo,
+) -> Result<()> {
+    let report = &attestation.report;
+    require!(report.kind == key.kind, E8004::AttestationInvalid);
+    require!(
+        report.timestamp >= requested_at
+            && report.timestamp <= now
+            && now - report.timestamp <= MAX_ATTESTATION_AGE,
+        E8004::AttestationInvalid
+    );
+    require!(
+        key.measurement == [0u8; 32] || report.measurement == key.measurement,
+        E8004::AttestationInvalid
+    );
+    require!(
+        report.report_data == keccak_bytes(&[request_hash.as_ref(), response_hash].concat()),
+        E8004::AttestationInvalid
+    );
+
+    require!(attestation.signature.len() == 64, E8004::BadSignature);
+    let digest = keccak_bytes(&report.try_to_vec()?);
+    require!(
+        verify_ed25519(&digest, &attestation.signature, &key.key, ix_sysvar),
+        E8004::BadSignature
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(key: Pubkey)]
+pub struct RegisterAttestationKeyCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 32 + 1 + 32 + 8 + 1,
+        seeds=[b"attkey", key.as_ref()],
+        bump
+    )]
+    pub attestation_key: Account<'info, AttestationKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_attestation_key(
+    mut ctx: Context<RegisterAttestationKeyCtx>,
+    key: Pubkey,
+    kind: u8,
+    measurement: [u8; 32],
+) -> Result<()> {
+    let key_bump = ctx.bumps.attestation_key;
+    require!(
+        kind == ATTESTATION_SGX || kind == ATTESTATION_NITRO,
+        E8004::InvalidConfig
+    );
+
+    let accounts = &mut ctx.accounts;
+    let k = &mut accounts.attestation_key;
+    k.key = key;
+    k.kind = kind;
+    k.measurement = measurement;
+    k.registered_at = Clock::get()?.unix_timestamp;
+    k.bump = key_bump;
+
+    emit!(AttestationKeyRegistered {
+        key,
+        kind,
+        measurement
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveAttestationKeyCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"attkey", attestation_key.key.as_ref()],
+        bump = attestation_key.bump,
+        close = authority
+    )]
+    pub attestation_key: Account<'info, AttestationKey>,
+}
+
+/// Drops a key from the allowlist. Responses it attested earlier keep their
+/// `attested` flag.
+pub fn remove_attestation_key(ctx: Context<RemoveAttestationKeyCtx>) -> Result<()> {
+    emit!(AttestationKeyRemoved {
+        key: ctx.accounts.attestation_key.key
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AttestationKeyRegistered {
+    pub key: Pubkey,
+    pub kind: u8,
+    pub measurement: [u8; 32],
+}
+
+#[event]
+pub struct AttestationKeyRemoved {
+    pub key: Pubkey,
+}
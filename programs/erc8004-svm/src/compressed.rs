@@ -0,0 +1,499 @@
+use crate::identity::{Agent, Platform};
+use crate::reputation::{
+    update_feedback_tags, verify_feedback_auth, ClientIndex, Feedback, ReputationConfig,
+    ReputationSummary, FEEDBACK_VERSION_SCORE, MAX_FILE_URI,
+};
+use crate::E8004;
+use anchor_lang::prelude::*;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::invoke_signed;
+use solana_program::{keccak, pubkey, sysvar};
+
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+pub const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+// Anchor discriminators of the spl-account-compression instructions. The
+// published crate pins an older anchor-lang, so the CPIs are built by hand.
+const IX_INIT_EMPTY_MERKLE_TREE: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+const IX_APPEND: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+const IX_REPLACE_LEAF: [u8; 8] = [204, 165, 76, 100, 73, 147, 0, 128];
+
+/// A concurrent Merkle tree of compressed feedback. The tree itself belongs
+/// to spl-account-compression with the platform PDA as its authority.
+#[account]
+pub struct FeedbackTree {
+    pub merkle_tree: Pubkey,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+    pub num_leaves: u64,
+    pub bump: u8,
+}
+
+/// Feedback as committed to a tree leaf. Clients pass it back to revoke.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompressedFeedback {
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub index: u64,
+    pub score: u8,
+    pub tag1: [u8; 32],
+    pub tag2: [u8; 32],
+    pub file_hash: [u8; 32],
+    pub created_at: i64,
+}
+
+impl CompressedFeedback {
+    pub fn leaf(&self) -> [u8; 32] {
+        keccak::hashv(&[
+            self.agent.as_ref(),
+            self.client.as_ref(),
+            &self.index.to_le_bytes(),
+            &[self.score],
+            &self.tag1,
+            &self.tag2,
+            &self.file_hash,
+            &self.created_at.to_le_bytes(),
+        ])
+        .0
+    }
+
+    /// The equivalent `Feedback`, for the summary bookkeeping shared with
+    /// account-backed feedback.
+    fn to_feedback(&self) -> Feedback {
+        Feedback {
+            agent: self.agent,
+            client: self.client,
+            index: self.index,
+            score: self.score,
+            tag1: self.tag1,
+            tag2: self.tag2,
+            revoked: false,
+            file_uri: String::new(),
+            file_hash: self.file_hash,
+            bump: 0,
+            created_at: self.created_at,
+            created_slot: 0,
+            revoked_at: 0,
+            revoked_slot: 0,
+            version: FEEDBACK_VERSION_SCORE,
+            value: 0,
+            value_decimals: 0,
+            stake: 0,
+            struck: false,
+            revision: 0,
+            prev_hash: [0u8; 32],
+            amended_at: 0,
+            counted: true,
+        }
+    }
+}
+
+/// Invokes spl-account-compression on `merkle_tree`, signing as the platform.
+fn compression_cpi<'info>(
+    data: Vec<u8>,
+    merkle_tree: &AccountInfo<'info>,
+    platform: &Account<'info, Platform>,
+    noop: &AccountInfo<'info>,
+    proof: &[AccountInfo<'info>],
+) -> Result<()> {
+    let mut metas = vec![
+        AccountMeta::new(merkle_tree.key(), false),
+        AccountMeta::new_readonly(platform.key(), true),
+        AccountMeta::new_readonly(noop.key(), false),
+    ];
+    metas.extend(
+        proof
+            .iter()
+            .map(|a| AccountMeta::new_readonly(a.key(), false)),
+    );
+
+    let mut infos = vec![
+        merkle_tree.clone(),
+        platform.to_account_info(),
+        noop.clone(),
+    ];
+    infos.extend_from_slice(proof);
+
+    invoke_signed(
+        &Instruction {
+            program_id: SPL_ACCOUNT_COMPRESSION_ID,
+            accounts: metas,
+            data,
+        },
+        &infos,
+        &[&[b"platform", &[platform.bump]]],
+    )
+    .map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct InitFeedbackTreeCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: zeroed tree account allocated beforehand for the chosen depth
+    /// and buffer size; initialized by spl-account-compression.
+    #[account(mut, owner = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + 4 + 8 + 1,
+        seeds=[b"ftree", merkle_tree.key().as_ref()],
+        bump
+    )]
+    pub tree: Account<'info, FeedbackTree>,
+
+    /// CHECK: spl-noop, which carries the tree change logs.
+    #[account(address = SPL_NOOP_ID)]
+    pub noop: UncheckedAccount<'info>,
+
+    /// CHECK: the spl-account-compression program.
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_feedback_tree(
+    mut ctx: Context<InitFeedbackTreeCtx>,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<()> {
+    let tree_bump = ctx.bumps.tree;
+    let accounts = &mut ctx.accounts;
+
+    let mut data = IX_INIT_EMPTY_MERKLE_TREE.to_vec();
+    data.extend_from_slice(&max_depth.to_le_bytes());
+    data.extend_from_slice(&max_buffer_size.to_le_bytes());
+    compression_cpi(
+        data,
+        &accounts.merkle_tree,
+        &accounts.platform,
+        &accounts.noop,
+        &[],
+    )?;
+
+    let tree = &mut accounts.tree;
+    tree.merkle_tree = accounts.merkle_tree.key();
+    tree.max_depth = max_depth;
+    tree.max_buffer_size = max_buffer_size;
+    tree.num_leaves = 0;
+    tree.bump = tree_bump;
+
+    emit!(FeedbackTreeCreated {
+        merkle_tree: tree.merkle_tree,
+        max_depth,
+        max_buffer_size
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GiveFeedbackCompressedCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = ClientIndex::SPACE,
+        seeds=[b"idx", agent.key().as_ref(), client.key().as_ref()],
+        bump
+    )]
+    pub idx: Account<'info, ClientIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = ReputationSummary::SPACE,
+        seeds=[b"rsum", agent.key().as_ref()],
+        bump
+    )]
+    pub summary: Account<'info, ReputationSummary>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"platform"], bump = platform.bump)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        mut,
+        seeds=[b"ftree", merkle_tree.key().as_ref()],
+        bump = tree.bump,
+        has_one = merkle_tree
+    )]
+    pub tree: Account<'info, FeedbackTree>,
+
+    /// CHECK: checked against `tree` and by spl-account-compression.
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: spl-noop, which carries the tree change logs.
+    #[account(address = SPL_NOOP_ID)]
+    pub noop: UncheckedAccount<'info>,
+
+    /// CHECK: the spl-account-compression program.
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    #[account(address = sysvar::instructions::ID)]
+    pub ix_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Account-free variant of `give_feedback_ed25519`: the feedback is appended
+/// as a leaf of `merkle_tree` and only emitted in full. Indices and summaries
+/// are shared with account-backed feedback; tag summaries and, for a new
+/// client, the agent's current `ClientPage` go in remaining accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn give_feedback_compressed<'info>(
+    mut ctx: Context<'_, '_, '_, 'info, GiveFeedbackCompressedCtx<'info>>,
+    score: u8,
+    tag1: [u8; 32],
+    tag2: [u8; 32],
+    file_uri: String,
+    file_hash: [u8; 32],
+    auth_struct_bytes: Vec<u8>,
+    signature: Vec<u8>,
+    expected_index: u64,
+) -> Result<()> {
+    require!(score <= 100, E8004::InvalidScore);
+    require!(file_uri.len() <= MAX_FILE_URI, E8004::Unauthorized);
+
+    let idx_bump = ctx.bumps.idx;
+    let summary_bump = ctx.bumps.summary;
+    let accounts = &mut ctx.accounts;
+
+    let agent_key = accounts.agent.key();
+    let client_key = accounts.client.key();
+    let clock = Clock::get()?;
+
+    let auth_struct = verify_feedback_auth(
+        &accounts.agent,
+        &client_key,
+        auth_struct_bytes,
+        &signature,
+        &accounts.ix_sysvar,
+        clock.unix_timestamp,
+    )?;
+
+    let idx = &mut accounts.idx;
+    let new_client = idx.last_index == 0;
+    if new_client {
+        idx.agent = agent_key;
+        idx.client = client_key;
+        idx.bump = idx_bump;
+    }
+    let next_index = idx.last_index.checked_add(1).unwrap();
+    require!(expected_index == next_index, E8004::IndexLimit);
+    require!(next_index <= auth_struct.index_limit, E8004::IndexLimit);
+    idx.last_index = next_index;
+
+    let feedback = CompressedFeedback {
+        agent: agent_key,
+        client: client_key,
+        index: next_index,
+        score,
+        tag1,
+        tag2,
+        file_hash,
+        created_at: clock.unix_timestamp,
+    };
+    let leaf = feedback.leaf();
+
+    let mut data = IX_APPEND.to_vec();
+    data.extend_from_slice(&leaf);
+    compression_cpi(
+        data,
+        &accounts.merkle_tree,
+        &accounts.platform,
+        &accounts.noop,
+        &[],
+    )?;
+
+    let tree = &mut accounts.tree;
+    let leaf_index = tree.num_leaves;
+    tree.num_leaves = tree.num_leaves.checked_add(1).unwrap();
+
+    let summary = &mut accounts.summary;
+    if summary.agent == Pubkey::default() {
+        summary.agent = agent_key;
+        summary.bump = summary_bump;
+    }
+    let as_feedback = feedback.to_feedback();
+    let half_life = ReputationConfig::load(&accounts.config)?.half_life;
+    summary.add_feedback(&as_feedback, half_life)?;
+
+    let client_info = accounts.client.to_account_info();
+    let system_info = accounts.system_program.to_account_info();
+    if new_client {
+        summary.add_client(
+            ctx.remaining_accounts,
+            &client_key,
+            &client_info,
+            &system_info,
+        )?;
+    }
+
+    update_feedback_tags(
+        ctx.remaining_accounts,
+        &agent_key,
+        &tag1,
+        &tag2,
+        Some((&client_info, &system_info)),
+        |t| t.add_feedback(&as_feedback),
+    )?;
+
+    emit!(CompressedFeedbackAppended {
+        merkle_tree: tree.merkle_tree,
+        leaf_index,
+        leaf,
+        feedback,
+        file_uri
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(feedback: CompressedFeedback)]
+pub struct RevokeCompressedFeedbackCtx<'info> {
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Account<'info, ReputationSummary>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"platform"], bump = platform.bump)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(seeds=[b"ftree", merkle_tree.key().as_ref()], bump = tree.bump, has_one = merkle_tree)]
+    pub tree: Account<'info, FeedbackTree>,
+
+    /// CHECK: checked against `tree` and by spl-account-compression.
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: spl-noop, which carries the tree change logs.
+    #[account(address = SPL_NOOP_ID)]
+    pub noop: UncheckedAccount<'info>,
+
+    /// CHECK: the spl-account-compression program.
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub client: Signer<'info>,
+}
+
+/// Revokes compressed feedback by replacing its leaf with the empty leaf.
+/// The tree verifies `feedback` against `root` and the proof, so the emitted
+/// data is trusted for the summary update. Remaining accounts are the
+/// feedback's tag summaries (one per distinct non-zero tag) followed by the
+/// proof nodes.
+pub fn revoke_compressed_feedback<'info>(
+    mut ctx: Context<'_, '_, '_, 'info, RevokeCompressedFeedbackCtx<'info>>,
+    feedback: CompressedFeedback,
+    root: [u8; 32],
+    leaf_index: u32,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    require!(
+        feedback.client == accounts.client.key(),
+        E8004::Unauthorized
+    );
+
+    let zero = [0u8; 32];
+    let mut tag_count = (feedback.tag1 != zero) as usize;
+    if feedback.tag2 != zero && feedback.tag2 != feedback.tag1 {
+        tag_count += 1;
+    }
+    require!(
+        ctx.remaining_accounts.len() >= tag_count,
+        E8004::TagSummaryMissing
+    );
+    let (tag_summaries, proof) = ctx.remaining_accounts.split_at(tag_count);
+
+    let mut data = IX_REPLACE_LEAF.to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&feedback.leaf());
+    data.extend_from_slice(&zero);
+    data.extend_from_slice(&leaf_index.to_le_bytes());
+    compression_cpi(
+        data,
+        &accounts.merkle_tree,
+        &accounts.platform,
+        &accounts.noop,
+        proof,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let as_feedback = feedback.to_feedback();
+    let summary = &mut accounts.summary;
+    summary.revoked_count = summary.revoked_count.checked_add(1).unwrap();
+    let half_life = ReputationConfig::load(&accounts.config)?.half_life;
+    summary.remove_feedback(&as_feedback, now, half_life)?;
+
+    update_feedback_tags(
+        tag_summaries,
+        &feedback.agent,
+        &feedback.tag1,
+        &feedback.tag2,
+        None,
+        |t| t.remove_feedback(&as_feedback),
+    )?;
+
+    emit!(CompressedFeedbackRevoked {
+        merkle_tree: accounts.merkle_tree.key(),
+        leaf_index,
+        agent: feedback.agent,
+        client: feedback.client,
+        index: feedback.index,
+        revoked_at: now
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct FeedbackTreeCreated {
+    pub merkle_tree: Pubkey,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+}
+
+/// Full leaf data of compressed feedback; `leaf` is `feedback.leaf()`.
+#[event]
+pub struct CompressedFeedbackAppended {
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u64,
+    pub leaf: [u8; 32],
+    pub feedback: CompressedFeedback,
+    pub file_uri: String,
+}
+
+#[event]
+pub struct CompressedFeedbackRevoked {
+    pub merkle_tree: Pubkey,
+    pub leaf_index: u32,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub index: u64,
+    pub revoked_at: i64,
+}
@@ -0,0 +1,188 @@
+use crate::reputation::{
+    counted_summary, update_feedback_tags, Feedback, ReputationConfig, ReputationSummary,
+};
+use crate::{identity::Agent, E8004};
+use anchor_lang::prelude::*;
+
+pub const MAX_EVIDENCE_URI: usize = 256;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeStatus {
+    Open,
+    /// The arbiter kept the feedback.
+    Upheld,
+    /// The arbiter struck the feedback from the summaries.
+    Struck,
+}
+
+#[account]
+pub struct Dispute {
+    pub feedback: Pubkey,
+    pub agent: Pubkey,
+    pub opened_by: Pubkey,
+    pub evidence_uri: String,
+    pub evidence_hash: [u8; 32],
+    pub status: DisputeStatus,
+    pub opened_at: i64,
+    pub resolved_at: i64,
+    pub bump: u8,
+}
+
+/// Whether `dispute`, a feedback's `[b"dispute", feedback]` PDA, holds a
+/// dispute still waiting for the arbiter. The PDA usually does not exist.
+pub fn dispute_is_open(dispute: &AccountInfo) -> Result<bool> {
+    if dispute.data_is_empty() {
+        return Ok(false);
+    }
+    let dispute = Dispute::try_deserialize(&mut &dispute.try_borrow_data()?[..])?;
+    Ok(dispute.status == DisputeStatus::Open)
+}
+
+#[derive(Accounts)]
+pub struct OpenDisputeCtx<'info> {
+    #[account(has_one = owner)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        constraint = feedback.agent == agent.key() @ E8004::Unauthorized,
+        constraint = feedback.is_active() @ E8004::Unauthorized
+    )]
+    pub feedback: Account<'info, Feedback>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 32 + 4 + MAX_EVIDENCE_URI + 32 + 1 + 8 + 8 + 1,
+        seeds=[b"dispute", feedback.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Lets the agent owner contest a feedback. Each feedback can be disputed
+/// once; the arbiter's resolution is final.
+pub fn open_dispute(
+    mut ctx: Context<OpenDisputeCtx>,
+    evidence_uri: String,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    let dispute_bump = ctx.bumps.dispute;
+    require!(evidence_uri.len() <= MAX_EVIDENCE_URI, E8004::Unauthorized);
+
+    let accounts = &mut ctx.accounts;
+    let d = &mut accounts.dispute;
+    d.feedback = accounts.feedback.key();
+    d.agent = accounts.agent.key();
+    d.opened_by = accounts.owner.key();
+    d.evidence_uri = evidence_uri;
+    d.evidence_hash = evidence_hash;
+    d.status = DisputeStatus::Open;
+    d.opened_at = Clock::get()?.unix_timestamp;
+    d.resolved_at = 0;
+    d.bump = dispute_bump;
+
+    emit!(DisputeOpened {
+        dispute: d.key(),
+        feedback: d.feedback,
+        agent: d.agent,
+        evidence_hash
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveDisputeCtx<'info> {
+    #[account(
+        mut,
+        seeds=[b"dispute", feedback.key().as_ref()],
+        bump = dispute.bump,
+        has_one = feedback,
+        constraint = dispute.status == DisputeStatus::Open @ E8004::DisputeClosed
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(mut)]
+    pub feedback: Account<'info, Feedback>,
+
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Option<Account<'info, ReputationSummary>>,
+
+    #[account(seeds=[b"rcfg"], bump = config.bump, has_one = arbiter)]
+    pub config: Account<'info, ReputationConfig>,
+
+    pub arbiter: Signer<'info>,
+}
+
+/// Settles an open dispute. Striking takes the feedback out of the agent and
+/// tag summaries (tag summaries go in remaining accounts, as for revoke) and
+/// makes its stake slashable.
+pub fn resolve_dispute(mut ctx: Context<ResolveDisputeCtx>, strike: bool) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let now = Clock::get()?.unix_timestamp;
+    let feedback = &mut accounts.feedback;
+
+    // The client may have revoked while the dispute was open; there is
+    // nothing left to strike then.
+    if strike && feedback.is_active() {
+        if let Some(summary) = counted_summary(feedback, &mut accounts.summary)? {
+            summary.remove_feedback(feedback, now, accounts.config.half_life)?;
+
+            update_feedback_tags(
+                ctx.remaining_accounts,
+                &feedback.agent,
+                &feedback.tag1,
+                &feedback.tag2,
+                None,
+                |t| t.remove_feedback(feedback),
+            )?;
+        }
+    }
+    if strike {
+        feedback.struck = true;
+    }
+
+    let d = &mut accounts.dispute;
+    d.status = if strike {
+        DisputeStatus::Struck
+    } else {
+        DisputeStatus::Upheld
+    };
+    d.resolved_at = now;
+
+    emit!(DisputeResolved {
+        dispute: d.key(),
+        feedback: d.feedback,
+        agent: d.agent,
+        struck: strike,
+        arbiter: accounts.arbiter.key()
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub dispute: Pubkey,
+    pub feedback: Pubkey,
+    pub agent: Pubkey,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub dispute: Pubkey,
+    pub feedback: Pubkey,
+    pub agent: Pubkey,
+    pub struck: bool,
+    pub arbiter: Pubkey,
+}
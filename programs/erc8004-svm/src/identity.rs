@@ -30,6 +30,58 @@ pub struct MetaKV {
     pub bump: u8,
 }
 
+/// A key the agent owner lets request validation on the agent's behalf, at
+/// `[b"operator", agent, operator]`.
+#[account]
+pub struct AgentOperator {
+    pub agent: Pubkey,
+    pub operator: Pubkey,
+    /// Requests allowed per `window` seconds; 0 means unlimited.
+    pub max_requests: u32,
+    pub window: i64,
+    pub window_start: i64,
+    pub window_count: u32,
+    pub bump: u8,
+}
+
+impl AgentOperator {
+    /// Counts one request against the rate limit, opening a new window once
+    /// the current one has passed.
+    pub fn consume(&mut self, now: i64) -> Result<()> {
+        if self.max_requests == 0 {
+            return Ok(());
+        }
+        if now >= self.window_start.saturating_add(self.window) {
+            self.window_start = now;
+            self.window_count = 0;
+        }
+        require!(self.window_count < self.max_requests, E8004::RateLimited);
+        self.window_count += 1;
+        Ok(())
+    }
+}
+
+/// Checks that `signer` may act for `agent`: either as its owner or through
+/// an `AgentOperator` within its rate limit.
+pub(crate) fn authorize_agent_signer(
+    agent: &Account<Agent>,
+    signer: &Pubkey,
+    operator: &mut Option<Account<AgentOperator>>,
+    now: i64,
+) -> Result<()> {
+    if agent.owner == *signer {
+        return Ok(());
+    }
+    let Some(operator) = operator else {
+        return err!(E8004::Unauthorized);
+    };
+    require!(
+        operator.agent == agent.key() && operator.operator == *signer,
+        E8004::Unauthorized
+    );
+    operator.consume(now)
+}
+
 #[derive(Accounts)]
 pub struct InitPlatformCtx<'info> {
     #[account(
@@ -91,10 +143,7 @@ pub struct AgentRegisterCtx<'info> {
 
 pub fn agent_register(mut ctx: Context<AgentRegisterCtx>, token_uri: String) -> Result<()> {
     let agent_bump = ctx.bumps.agent;
-    require!(
-        token_uri.as_bytes().len() <= MAX_TOKEN_URI,
-        E8004::Unauthorized
-    );
+    require!(token_uri.len() <= MAX_TOKEN_URI, E8004::Unauthorized);
 
     let accounts = &mut ctx.accounts;
     let p = &mut accounts.platform;
@@ -167,6 +216,88 @@ pub fn agent_set_metadata(
     Ok(())
 }
 
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct SetAgentOperatorCtx<'info> {
+    #[account(has_one = owner)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + 32 + 32 + 4 + 8 + 8 + 4 + 1,
+        seeds=[b"operator", agent.key().as_ref(), operator.as_ref()],
+        bump
+    )]
+    pub agent_operator: Account<'info, AgentOperator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approves `operator` or updates its rate limit of `max_requests` per
+/// `window` seconds.
+pub fn set_agent_operator(
+    mut ctx: Context<SetAgentOperatorCtx>,
+    operator: Pubkey,
+    max_requests: u32,
+    window: i64,
+) -> Result<()> {
+    let operator_bump = ctx.bumps.agent_operator;
+    require!(max_requests == 0 || window > 0, E8004::InvalidConfig);
+
+    let accounts = &mut ctx.accounts;
+    require!(operator != accounts.owner.key(), E8004::Unauthorized);
+
+    let o = &mut accounts.agent_operator;
+    o.agent = accounts.agent.key();
+    o.operator = operator;
+    o.max_requests = max_requests;
+    o.window = window;
+    o.window_start = 0;
+    o.window_count = 0;
+    o.bump = operator_bump;
+
+    emit!(AgentOperatorSet {
+        agent: o.agent,
+        operator,
+        max_requests,
+        window
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeAgentOperatorCtx<'info> {
+    #[account(has_one = owner)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"operator", agent.key().as_ref(), agent_operator.operator.as_ref()],
+        bump = agent_operator.bump,
+        close = owner
+    )]
+    pub agent_operator: Account<'info, AgentOperator>,
+}
+
+pub fn revoke_agent_operator(ctx: Context<RevokeAgentOperatorCtx>) -> Result<()> {
+    let o = &ctx.accounts.agent_operator;
+
+    emit!(AgentOperatorRevoked {
+        agent: o.agent,
+        operator: o.operator
+    });
+
+    Ok(())
+}
+
 #[event]
 pub struct AgentRegistered {
     pub agent: Pubkey,
@@ -179,3 +310,17 @@ pub struct MetadataSet {
     pub agent: Pubkey,
     pub key_hash: [u8; 32],
 }
+
+#[event]
+pub struct AgentOperatorSet {
+    pub agent: Pubkey,
+    pub operator: Pubkey,
+    pub max_requests: u32,
+    pub window: i64,
+}
+
+#[event]
+pub struct AgentOperatorRevoked {
+    pub agent: Pubkey,
+    pub operator: Pubkey,
+}
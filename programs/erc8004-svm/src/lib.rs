@@ -2,41 +2,121 @@
 
 use anchor_lang::prelude::*;
 
+pub mod attestation;
+pub mod compressed;
+pub mod dispute;
 pub mod identity;
 pub mod reputation;
+pub mod stake;
+pub mod tags;
 pub mod util;
 pub mod validation;
+pub mod validator;
 
-use identity::{AgentRegisterCtx, AgentSetMetaCtx, InitPlatformCtx};
-use reputation::{GiveFeedbackCtx, RevokeFeedbackCtx};
-use validation::{ValidationRequestCtx, ValidationResponseCtx};
+use attestation::{Attestation, RegisterAttestationKeyCtx, RemoveAttestationKeyCtx};
+use compressed::{
+    CompressedFeedback, GiveFeedbackCompressedCtx, InitFeedbackTreeCtx, RevokeCompressedFeedbackCtx,
+};
+use dispute::{OpenDisputeCtx, ResolveDisputeCtx};
+use identity::{
+    AgentRegisterCtx, AgentSetMetaCtx, InitPlatformCtx, RevokeAgentOperatorCtx, SetAgentOperatorCtx,
+};
+use reputation::{
+    AmendFeedbackCtx, CloseFeedbackCtx, DecayedScore, FeedbackEntry, FeedbackSummary,
+    GetClientsCtx, GetDecayedScoreCtx, GetFeedbackSummaryCtx, GetStakeWeightedScoreCtx,
+    GetTagSummariesCtx, GiveFeedbackBatchCtx, GiveFeedbackCtx, MigrateFeedbackCtx,
+    RevokeFeedbackCtx, SetReputationConfigCtx, StakeWeightedScore, TagScore,
+};
+use stake::{SlashFeedbackStakeCtx, StakeFeedbackCtx, UnstakeFeedbackCtx};
+use tags::RegisterTagCtx;
+use validation::{
+    GetValidationSummaryCtx, ValidationCancelCtx, ValidationCloseCtx, ValidationDeclineCtx,
+    ValidationExpireCtx, ValidationQuorumCloseCtx, ValidationQuorumExpireCtx,
+    ValidationQuorumRequestCtx, ValidationQuorumResponseCtx, ValidationRequestCtx,
+    ValidationResponseCtx, ValidationScore,
+};
+use validator::{
+    ChallengeResponseCtx, RegisterValidatorCtx, SetValidationPolicyCtx, SetValidatorConfigCtx,
+    SlashValidatorCtx, UpdateValidatorCtx, WithdrawValidatorStakeCtx,
+};
 
 // === Re-export client account ctx builders for Anchor 0.30 ===
 // These are emitted by Anchor build tooling normally. Since we compile
 // with plain `cargo build`, we expose them manually from our modules.
 
+pub(crate) use attestation::{
+    __client_accounts_register_attestation_key_ctx, __client_accounts_remove_attestation_key_ctx,
+};
+
+pub(crate) use compressed::{
+    __client_accounts_give_feedback_compressed_ctx, __client_accounts_init_feedback_tree_ctx,
+    __client_accounts_revoke_compressed_feedback_ctx,
+};
+
+pub(crate) use dispute::{
+    __client_accounts_open_dispute_ctx, __client_accounts_resolve_dispute_ctx,
+};
+
 pub(crate) use identity::{
     __client_accounts_agent_register_ctx, __client_accounts_agent_set_meta_ctx,
-    __client_accounts_init_platform_ctx,
+    __client_accounts_init_platform_ctx, __client_accounts_revoke_agent_operator_ctx,
+    __client_accounts_set_agent_operator_ctx,
 };
 
 pub(crate) use reputation::{
-    __client_accounts_give_feedback_ctx, __client_accounts_revoke_feedback_ctx,
+    __client_accounts_amend_feedback_ctx, __client_accounts_close_feedback_ctx,
+    __client_accounts_get_clients_ctx, __client_accounts_get_decayed_score_ctx,
+    __client_accounts_get_feedback_summary_ctx, __client_accounts_get_stake_weighted_score_ctx,
+    __client_accounts_get_tag_summaries_ctx, __client_accounts_give_feedback_batch_ctx,
+    __client_accounts_give_feedback_ctx, __client_accounts_migrate_feedback_ctx,
+    __client_accounts_revoke_feedback_ctx, __client_accounts_set_reputation_config_ctx,
 };
 
+pub(crate) use stake::{
+    __client_accounts_slash_feedback_stake_ctx, __client_accounts_stake_feedback_ctx,
+    __client_accounts_unstake_feedback_ctx,
+};
+
+pub(crate) use tags::__client_accounts_register_tag_ctx;
+
 pub(crate) use validation::{
-    __client_accounts_validation_request_ctx, __client_accounts_validation_response_ctx,
+    __client_accounts_get_validation_summary_ctx, __client_accounts_validation_cancel_ctx,
+    __client_accounts_validation_close_ctx, __client_accounts_validation_decline_ctx,
+    __client_accounts_validation_expire_ctx, __client_accounts_validation_quorum_close_ctx,
+    __client_accounts_validation_quorum_expire_ctx,
+    __client_accounts_validation_quorum_request_ctx,
+    __client_accounts_validation_quorum_response_ctx, __client_accounts_validation_request_ctx,
+    __client_accounts_validation_response_ctx,
+};
+
+pub(crate) use validator::{
+    __client_accounts_challenge_response_ctx, __client_accounts_register_validator_ctx,
+    __client_accounts_set_validation_policy_ctx, __client_accounts_set_validator_config_ctx,
+    __client_accounts_slash_validator_ctx, __client_accounts_update_validator_ctx,
+    __client_accounts_withdraw_validator_stake_ctx,
 };
 
 // Anchor 0.30 expects build tooling to emit these modules for the `client` feature.
 // We compile the program directly with `cargo build`, so we provide empty stubs to
 // satisfy the references emitted by the `#[program]` macro.
 #[allow(non_snake_case)]
+pub mod __client_accounts_attestation {}
+#[allow(non_snake_case)]
+pub mod __client_accounts_compressed {}
+#[allow(non_snake_case)]
+pub mod __client_accounts_dispute {}
+#[allow(non_snake_case)]
 pub mod __client_accounts_identity {}
 #[allow(non_snake_case)]
 pub mod __client_accounts_reputation {}
 #[allow(non_snake_case)]
+pub mod __client_accounts_stake {}
+#[allow(non_snake_case)]
+pub mod __client_accounts_tags {}
+#[allow(non_snake_case)]
 pub mod __client_accounts_validation {}
+#[allow(non_snake_case)]
+pub mod __client_accounts_validator {}
 
 declare_id!("F3471nQ1BYRVUL2RUGRfC5JToakHkweBmLAMoMFBjo9d");
 
@@ -61,8 +141,22 @@ pub mod erc8004_svm {
         identity::agent_set_metadata(ctx, key, value, key_hash)
     }
 
-    pub fn give_feedback_ed25519(
-        ctx: Context<GiveFeedbackCtx>,
+    pub fn set_agent_operator(
+        ctx: Context<SetAgentOperatorCtx>,
+        operator: Pubkey,
+        max_requests: u32,
+        window: i64,
+    ) -> Result<()> {
+        identity::set_agent_operator(ctx, operator, max_requests, window)
+    }
+
+    pub fn revoke_agent_operator(ctx: Context<RevokeAgentOperatorCtx>) -> Result<()> {
+        identity::revoke_agent_operator(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn give_feedback_ed25519<'info>(
+        ctx: Context<'_, '_, '_, 'info, GiveFeedbackCtx<'info>>,
         score: u8,
         tag1: [u8; 32],
         tag2: [u8; 32],
@@ -71,6 +165,9 @@ pub mod erc8004_svm {
         auth_struct_bytes: Vec<u8>,
         signature: Vec<u8>,
         expected_index: u64,
+        tag1_label: Option<String>,
+        tag2_label: Option<String>,
+        payload: Option<Vec<u8>>,
     ) -> Result<()> {
         reputation::give_feedback_ed25519(
             ctx,
@@ -82,20 +179,273 @@ pub mod erc8004_svm {
             auth_struct_bytes,
             signature,
             expected_index,
+            tag1_label,
+            tag2_label,
+            payload,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn give_feedback_value_ed25519<'info>(
+        ctx: Context<'_, '_, '_, 'info, GiveFeedbackCtx<'info>>,
+        value: i128,
+        value_decimals: u8,
+        tag1: [u8; 32],
+        tag2: [u8; 32],
+        file_uri: String,
+        file_hash: [u8; 32],
+        auth_struct_bytes: Vec<u8>,
+        signature: Vec<u8>,
+        expected_index: u64,
+        tag1_label: Option<String>,
+        tag2_label: Option<String>,
+        payload: Option<Vec<u8>>,
+    ) -> Result<()> {
+        reputation::give_feedback_value_ed25519(
+            ctx,
+            value,
+            value_decimals,
+            tag1,
+            tag2,
+            file_uri,
+            file_hash,
+            auth_struct_bytes,
+            signature,
+            expected_index,
+            tag1_label,
+            tag2_label,
+            payload,
+        )
+    }
+
+    pub fn give_feedback_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, GiveFeedbackBatchCtx<'info>>,
+        entries: Vec<FeedbackEntry>,
+    ) -> Result<()> {
+        reputation::give_feedback_batch(ctx, entries)
+    }
+
+    pub fn init_feedback_tree(
+        ctx: Context<InitFeedbackTreeCtx>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        compressed::init_feedback_tree(ctx, max_depth, max_buffer_size)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn give_feedback_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, GiveFeedbackCompressedCtx<'info>>,
+        score: u8,
+        tag1: [u8; 32],
+        tag2: [u8; 32],
+        file_uri: String,
+        file_hash: [u8; 32],
+        auth_struct_bytes: Vec<u8>,
+        signature: Vec<u8>,
+        expected_index: u64,
+    ) -> Result<()> {
+        compressed::give_feedback_compressed(
+            ctx,
+            score,
+            tag1,
+            tag2,
+            file_uri,
+            file_hash,
+            auth_struct_bytes,
+            signature,
+            expected_index,
         )
     }
 
+    pub fn revoke_compressed_feedback<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeCompressedFeedbackCtx<'info>>,
+        feedback: CompressedFeedback,
+        root: [u8; 32],
+        leaf_index: u32,
+    ) -> Result<()> {
+        compressed::revoke_compressed_feedback(ctx, feedback, root, leaf_index)
+    }
+
     pub fn revoke_feedback(ctx: Context<RevokeFeedbackCtx>, index: u64) -> Result<()> {
         reputation::revoke_feedback(ctx, index)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn amend_feedback<'info>(
+        ctx: Context<'_, '_, '_, 'info, AmendFeedbackCtx<'info>>,
+        score: u8,
+        tag1: [u8; 32],
+        tag2: [u8; 32],
+        file_uri: String,
+        file_hash: [u8; 32],
+        tag1_label: Option<String>,
+        tag2_label: Option<String>,
+    ) -> Result<()> {
+        reputation::amend_feedback(
+            ctx, score, tag1, tag2, file_uri, file_hash, tag1_label, tag2_label,
+        )
+    }
+
+    pub fn close_feedback(ctx: Context<CloseFeedbackCtx>) -> Result<()> {
+        reputation::close_feedback(ctx)
+    }
+
+    pub fn migrate_feedback(ctx: Context<MigrateFeedbackCtx>) -> Result<()> {
+        reputation::migrate_feedback(ctx)
+    }
+
+    pub fn set_reputation_config(
+        ctx: Context<SetReputationConfigCtx>,
+        half_life: i64,
+        stake_mint: Option<Pubkey>,
+        stake_cooldown: i64,
+        arbiter: Pubkey,
+    ) -> Result<()> {
+        reputation::set_reputation_config(ctx, half_life, stake_mint, stake_cooldown, arbiter)
+    }
+
+    pub fn get_stake_weighted_score(
+        ctx: Context<GetStakeWeightedScoreCtx>,
+    ) -> Result<StakeWeightedScore> {
+        reputation::get_stake_weighted_score(ctx)
+    }
+
+    pub fn open_dispute(
+        ctx: Context<OpenDisputeCtx>,
+        evidence_uri: String,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        dispute::open_dispute(ctx, evidence_uri, evidence_hash)
+    }
+
+    pub fn resolve_dispute(ctx: Context<ResolveDisputeCtx>, strike: bool) -> Result<()> {
+        dispute::resolve_dispute(ctx, strike)
+    }
+
+    pub fn stake_feedback(ctx: Context<StakeFeedbackCtx>, amount: u64) -> Result<()> {
+        stake::stake_feedback(ctx, amount)
+    }
+
+    pub fn unstake_feedback(ctx: Context<UnstakeFeedbackCtx>) -> Result<()> {
+        stake::unstake_feedback(ctx)
+    }
+
+    pub fn slash_feedback_stake(ctx: Context<SlashFeedbackStakeCtx>) -> Result<()> {
+        stake::slash_feedback_stake(ctx)
+    }
+
+    pub fn get_decayed_score(ctx: Context<GetDecayedScoreCtx>) -> Result<DecayedScore> {
+        reputation::get_decayed_score(ctx)
+    }
+
+    pub fn get_tag_summaries(
+        ctx: Context<GetTagSummariesCtx>,
+        tags: Vec<[u8; 32]>,
+    ) -> Result<Vec<TagScore>> {
+        reputation::get_tag_summaries(ctx, tags)
+    }
+
+    pub fn get_clients(ctx: Context<GetClientsCtx>, page: u32) -> Result<Vec<Pubkey>> {
+        reputation::get_clients(ctx, page)
+    }
+
+    pub fn get_feedback_summary(
+        ctx: Context<GetFeedbackSummaryCtx>,
+        client_addresses: Vec<Pubkey>,
+        tag1: [u8; 32],
+        tag2: [u8; 32],
+    ) -> Result<FeedbackSummary> {
+        reputation::get_feedback_summary(ctx, client_addresses, tag1, tag2)
+    }
+
     pub fn validation_request(
         ctx: Context<ValidationRequestCtx>,
         validator: Pubkey,
         request_uri: String,
         request_hash: [u8; 32],
+        deadline: i64,
+        bounty: u64,
     ) -> Result<()> {
-        validation::validation_request(ctx, validator, request_uri, request_hash)
+        validation::validation_request(ctx, validator, request_uri, request_hash, deadline, bounty)
+    }
+
+    pub fn validation_quorum_request(
+        ctx: Context<ValidationQuorumRequestCtx>,
+        validators: Vec<Pubkey>,
+        threshold: u8,
+        request_uri: String,
+        request_hash: [u8; 32],
+        deadline: i64,
+    ) -> Result<()> {
+        validation::validation_quorum_request(
+            ctx,
+            validators,
+            threshold,
+            request_uri,
+            request_hash,
+            deadline,
+        )
+    }
+
+    pub fn validation_quorum_response(
+        ctx: Context<ValidationQuorumResponseCtx>,
+        response: u8,
+        response_uri: String,
+        response_hash: [u8; 32],
+        tag: [u8; 32],
+        tag_label: Option<String>,
+    ) -> Result<()> {
+        validation::validation_quorum_response(
+            ctx,
+            response,
+            response_uri,
+            response_hash,
+            tag,
+            tag_label,
+        )
+    }
+
+    pub fn validation_quorum_expire<'info>(
+        ctx: Context<'_, '_, '_, 'info, ValidationQuorumExpireCtx<'info>>,
+    ) -> Result<()> {
+        validation::validation_quorum_expire(ctx)
+    }
+
+    pub fn validation_quorum_close<'info>(
+        ctx: Context<'_, '_, '_, 'info, ValidationQuorumCloseCtx<'info>>,
+    ) -> Result<()> {
+        validation::validation_quorum_close(ctx)
+    }
+
+    pub fn get_validation_summary(
+        ctx: Context<GetValidationSummaryCtx>,
+        validators: Vec<Pubkey>,
+        tag: [u8; 32],
+    ) -> Result<ValidationScore> {
+        validation::get_validation_summary(ctx, validators, tag)
+    }
+
+    pub fn validation_decline(
+        ctx: Context<ValidationDeclineCtx>,
+        reason: u8,
+        reason_uri: String,
+    ) -> Result<()> {
+        validation::validation_decline(ctx, reason, reason_uri)
+    }
+
+    pub fn validation_expire(ctx: Context<ValidationExpireCtx>) -> Result<()> {
+        validation::validation_expire(ctx)
+    }
+
+    pub fn validation_cancel(ctx: Context<ValidationCancelCtx>) -> Result<()> {
+        validation::validation_cancel(ctx)
+    }
+
+    pub fn validation_close<'info>(
+        ctx: Context<'_, '_, '_, 'info, ValidationCloseCtx<'info>>,
+    ) -> Result<()> {
+        validation::validation_close(ctx)
     }
 
     pub fn validation_response(
@@ -104,8 +454,102 @@ pub mod erc8004_svm {
         response_uri: String,
         response_hash: [u8; 32],
         tag: [u8; 32],
+        tag_label: Option<String>,
+        attestation: Option<Attestation>,
+    ) -> Result<()> {
+        validation::validation_response(
+            ctx,
+            response,
+            response_uri,
+            response_hash,
+            tag,
+            tag_label,
+            attestation,
+        )
+    }
+
+    pub fn set_validator_config(
+        ctx: Context<SetValidatorConfigCtx>,
+        stake_mint: Option<Pubkey>,
+        min_stake: u64,
+        unbond_period: i64,
+    ) -> Result<()> {
+        validator::set_validator_config(ctx, stake_mint, min_stake, unbond_period)
+    }
+
+    pub fn register_validator(
+        ctx: Context<RegisterValidatorCtx>,
+        methods: u8,
+        metadata_uri: String,
+        amount: u64,
+    ) -> Result<()> {
+        validator::register_validator(ctx, methods, metadata_uri, amount)
+    }
+
+    pub fn update_validator(
+        ctx: Context<UpdateValidatorCtx>,
+        methods: u8,
+        metadata_uri: String,
+    ) -> Result<()> {
+        validator::update_validator(ctx, methods, metadata_uri)
+    }
+
+    pub fn unbond_validator(ctx: Context<UpdateValidatorCtx>) -> Result<()> {
+        validator::unbond_validator(ctx)
+    }
+
+    pub fn withdraw_validator_stake(ctx: Context<WithdrawValidatorStakeCtx>) -> Result<()> {
+        validator::withdraw_validator_stake(ctx)
+    }
+
+    pub fn slash_validator(
+        ctx: Context<SlashValidatorCtx>,
+        amount: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        validator::slash_validator(ctx, amount, evidence_hash)
+    }
+
+    pub fn challenge_response(ctx: Context<ChallengeResponseCtx>, challenged: bool) -> Result<()> {
+        validator::challenge_response(ctx, challenged)
+    }
+
+    pub fn set_validation_policy(
+        ctx: Context<SetValidationPolicyCtx>,
+        require_registered: bool,
+        required_methods: u8,
+        min_stake: u64,
+        stake_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        validator::set_validation_policy(
+            ctx,
+            require_registered,
+            required_methods,
+            min_stake,
+            stake_mint,
+        )
+    }
+
+    pub fn register_attestation_key(
+        ctx: Context<RegisterAttestationKeyCtx>,
+        key: Pubkey,
+        kind: u8,
+        measurement: [u8; 32],
+    ) -> Result<()> {
+        attestation::register_attestation_key(ctx, key, kind, measurement)
+    }
+
+    pub fn remove_attestation_key(ctx: Context<RemoveAttestationKeyCtx>) -> Result<()> {
+        attestation::remove_attestation_key(ctx)
+    }
+
+    pub fn register_tag(
+        ctx: Context<RegisterTagCtx>,
+        tag_hash: [u8; 32],
+        tag: String,
+        description: String,
     ) -> Result<()> {
-        validation::validation_response(ctx, response, response_uri, response_hash, tag)
+        tags::register_tag(ctx, tag_hash, tag, description)
     }
 }
 
@@ -131,4 +575,70 @@ pub enum E8004 {
     RequestNotFound,
     #[msg("Signature invalid")]
     BadSignature,
+    #[msg("Reputation summary account missing")]
+    SummaryMissing,
+    #[msg("Tag summary account missing")]
+    TagSummaryMissing,
+    #[msg("Too many tags requested")]
+    TooManyTags,
+    #[msg("Duplicate account")]
+    DuplicateAccount,
+    #[msg("Invalid config")]
+    InvalidConfig,
+    #[msg("Account already migrated")]
+    AlreadyMigrated,
+    #[msg("Feedback not revoked")]
+    NotRevoked,
+    #[msg("Invalid value")]
+    InvalidValue,
+    #[msg("Tag does not match its hash")]
+    TagMismatch,
+    #[msg("Inline payload too large")]
+    PayloadTooLarge,
+    #[msg("Inline payload does not match file hash")]
+    PayloadMismatch,
+    #[msg("Invalid feedback batch")]
+    InvalidBatch,
+    #[msg("Invalid stake")]
+    InvalidStake,
+    #[msg("Stake still locked")]
+    StakeLocked,
+    #[msg("Token accounts missing")]
+    MissingTokenAccounts,
+    #[msg("Mint mismatch")]
+    MintMismatch,
+    #[msg("Feedback not struck")]
+    NotStruck,
+    #[msg("Dispute already resolved")]
+    DisputeClosed,
+    #[msg("Feedback is under dispute")]
+    DisputeOpen,
+    #[msg("Client page account missing")]
+    ClientPageMissing,
+    #[msg("Request expired")]
+    RequestExpired,
+    #[msg("Request not expired")]
+    NotExpired,
+    #[msg("Request not finalized")]
+    NotFinalized,
+    #[msg("Response account missing")]
+    ResponseMissing,
+    #[msg("Validator not registered or not accepted by agent policy")]
+    ValidatorNotRegistered,
+    #[msg("Response already slashed")]
+    AlreadySlashed,
+    #[msg("Response is challenged")]
+    ResponseChallenged,
+    #[msg("Invalid quorum")]
+    InvalidQuorum,
+    #[msg("Request already finalized")]
+    AlreadyFinalized,
+    #[msg("Validation summary account missing")]
+    ValidationSummaryMissing,
+    #[msg("Request declined by validator")]
+    RequestDeclined,
+    #[msg("Operator rate limit reached")]
+    RateLimited,
+    #[msg("Attestation invalid")]
+    AttestationInvalid,
 }
@@ -1,11 +1,39 @@
-use crate::util::{decode_auth_struct, keccak_auth_struct, verify_ed25519};
-use crate::{identity::Agent, E8004};
+use crate::dispute::dispute_is_open;
+use crate::tags::verify_tag_label;
+use crate::util::{
+    create_pda_account, decay_factor, decode_auth_struct, keccak_auth_struct, keccak_bytes,
+    load_or_create_pda, store_account, verify_ed25519, FeedbackAuth, DECAY_SCALE,
+};
+use crate::{
+    identity::{Agent, Platform},
+    E8004,
+};
 use anchor_lang::prelude::*;
-use solana_program::sysvar;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use solana_program::{keccak, sysvar};
 
 pub const MAX_FILE_URI: usize = 256;
+pub const MAX_TAG_QUERY: usize = 16;
+/// Largest feedback file that can be submitted inline instead of by URI.
+pub const MAX_INLINE_PAYLOAD: usize = 512;
+pub const MAX_FEEDBACK_BATCH: usize = 8;
+pub const CLIENTS_PER_PAGE: usize = 32;
+
+/// `Feedback.version` of the original 0-100 `score` feedback.
+pub const FEEDBACK_VERSION_SCORE: u8 = 0;
+/// `Feedback.version` of signed fixed-point `value` feedback.
+pub const FEEDBACK_VERSION_VALUE: u8 = 1;
+/// Decimals that values are normalized to before they are aggregated.
+pub const VALUE_DECIMALS: u8 = 18;
+/// Largest magnitude of a normalized value, 10^12 whole units. Keeps the
+/// per-tag `value_sum` far from `i128` overflow.
+pub const MAX_VALUE: i128 = 1_000_000_000_000 * 10i128.pow(VALUE_DECIMALS as u32);
+/// Half-life of feedback weight, in seconds, before a `ReputationConfig` is set.
+pub const DEFAULT_HALF_LIFE: i64 = 30 * 86_400;
 
 #[account]
+#[derive(Default)]
 pub struct ClientIndex {
     pub agent: Pubkey,
     pub client: Pubkey,
@@ -13,6 +41,26 @@ pub struct ClientIndex {
     pub bump: u8,
 }
 
+impl ClientIndex {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+/// Append-only page of an agent's clients in the order they first gave
+/// feedback; client `n` sits on page `n / CLIENTS_PER_PAGE`. Clients whose
+/// first feedback predates the list are not on it.
+#[account]
+#[derive(Default)]
+pub struct ClientPage {
+    pub agent: Pubkey,
+    pub page: u32,
+    pub clients: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl ClientPage {
+    pub const SPACE: usize = 8 + 32 + 4 + 4 + 32 * CLIENTS_PER_PAGE + 1;
+}
+
 #[account]
 pub struct Feedback {
     pub agent: Pubkey,
@@ -25,10 +73,470 @@ pub struct Feedback {
     pub file_uri: String,
     pub file_hash: [u8; 32],
     pub bump: u8,
+    /// Set when the feedback was added to the agent and tag summaries.
+    /// Feedback given before they existed never was, so revoking, amending or
+    /// striking it leaves them alone.
+    pub counted: bool,
+    pub created_at: i64,
+    pub created_slot: u64,
+    pub revoked_at: i64,
+    pub revoked_slot: u64,
+    pub version: u8,
+    pub value: i128,
+    pub value_decimals: u8,
+    pub stake: u64,
+    /// Set when an arbiter upholds a dispute against this feedback.
+    pub struck: bool,
+    /// Number of times the client amended this feedback.
+    pub revision: u32,
+    /// `content_hash` of the previous revision; zero for the original.
+    pub prev_hash: [u8; 32],
+    pub amended_at: i64,
+}
+
+impl Feedback {
+    pub const SPACE: usize = 8
+        + 32
+        + 32
+        + 8
+        + 1
+        + 32
+        + 32
+        + 1
+        + 4
+        + MAX_FILE_URI
+        + 32
+        + 1
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 16
+        + 1
+        + 8
+        + 1
+        + 4
+        + 32
+        + 8;
+
+    /// Whether the feedback is neither revoked nor struck.
+    pub fn is_active(&self) -> bool {
+        !self.revoked && !self.struck
+    }
+
+    /// Whether the feedback currently contributes to the summaries.
+    pub fn in_summaries(&self) -> bool {
+        self.counted && self.is_active()
+    }
+
+    pub fn is_scored(&self) -> bool {
+        self.version == FEEDBACK_VERSION_SCORE
+    }
+
+    /// Hash of the current revision, chained to the previous one through
+    /// `prev_hash`.
+    pub fn content_hash(&self) -> [u8; 32] {
+        keccak::hashv(&[
+            &self.index.to_le_bytes(),
+            &self.revision.to_le_bytes(),
+            &[self.version, self.score],
+            &self.value.to_le_bytes(),
+            &[self.value_decimals],
+            &self.tag1,
+            &self.tag2,
+            self.file_uri.as_bytes(),
+            &self.file_hash,
+            &self.prev_hash,
+        ])
+        .0
+    }
+
+    /// The value of value feedback in `VALUE_DECIMALS` fixed point. Values
+    /// have no common scale with 0-100 scores, so the two are never summed
+    /// together.
+    pub fn normalized_value(&self) -> Result<i128> {
+        normalize_value(self.value, self.value_decimals)
+    }
+}
+
+/// Scales `value` with `decimals` decimals to `VALUE_DECIMALS`, rejecting
+/// magnitudes above `MAX_VALUE`.
+pub fn normalize_value(value: i128, decimals: u8) -> Result<i128> {
+    require!(decimals <= VALUE_DECIMALS, E8004::InvalidValue);
+    let normalized = value
+        .checked_mul(10i128.pow((VALUE_DECIMALS - decimals) as u32))
+        .ok_or(E8004::InvalidValue)?;
+    require!(
+        normalized.unsigned_abs() <= MAX_VALUE as u128,
+        E8004::InvalidValue
+    );
+    Ok(normalized)
+}
+
+/// Left behind by `close_feedback` so a closed index stays accounted for
+/// after the `Feedback` rent is reclaimed.
+#[account]
+pub struct FeedbackTombstone {
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub index: u64,
+    pub revoked_at: i64,
+    pub closed_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct ReputationConfig {
+    pub half_life: i64,
+    pub bump: u8,
+    /// Asset feedback is staked in; `None` stakes SOL.
+    pub stake_mint: Option<Pubkey>,
+    pub stake_cooldown: i64,
+    /// Settles disputes and may slash feedback stakes.
+    pub arbiter: Pubkey,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        ReputationConfig {
+            half_life: DEFAULT_HALF_LIFE,
+            bump: 0,
+            stake_mint: None,
+            stake_cooldown: 0,
+            arbiter: Pubkey::default(),
+        }
+    }
+}
+
+impl ReputationConfig {
+    /// Reads the `[b"rcfg"]` config. Until the platform authority sets one,
+    /// feedback decays with `DEFAULT_HALF_LIFE`, stakes are in SOL without a
+    /// cooldown and nobody can arbitrate disputes.
+    pub fn load(info: &AccountInfo) -> Result<Self> {
+        if info.data_is_empty() {
+            return Ok(Self::default());
+        }
+        Self::try_deserialize(&mut &info.try_borrow_data()?[..])
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct ReputationSummary {
+    pub agent: Pubkey,
+    pub feedback_count: u64,
+    pub active_count: u64,
+    pub revoked_count: u64,
+    pub score_sum: u64,
+    pub score_sq_sum: u64,
+    pub last_feedback_at: i64,
+    pub bump: u8,
+    pub decayed_score_sum: u128,
+    pub decayed_weight: u128,
+    pub decay_updated_at: i64,
+    pub score_count: u64,
+    pub stake_total: u128,
+    pub stake_weighted_score_sum: u128,
+    /// Clients on the agent's `ClientPage`s.
+    pub client_count: u64,
+}
+
+impl ReputationSummary {
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 16 + 16 + 8 + 8 + 16 + 16 + 8;
+
+    /// Brings the decayed sums forward to `now`. Both sums decay by the same
+    /// factor, so the decayed average only moves when feedback changes while
+    /// `decayed_weight` keeps shrinking as the reputation goes stale.
+    pub fn decay_to(&mut self, now: i64, half_life: i64) {
+        let f = decay_factor(now - self.decay_updated_at, half_life);
+        self.decayed_score_sum = self.decayed_score_sum * f / DECAY_SCALE;
+        self.decayed_weight = self.decayed_weight * f / DECAY_SCALE;
+        self.decay_updated_at = now;
+    }
+
+    pub fn add_feedback(&mut self, feedback: &Feedback, half_life: i64) -> Result<()> {
+        self.feedback_count = self.feedback_count.checked_add(1).unwrap();
+        self.active_count = self.active_count.checked_add(1).unwrap();
+        self.last_feedback_at = feedback.created_at;
+
+        if feedback.is_scored() {
+            let score = feedback.score as u64;
+            self.score_count = self.score_count.checked_add(1).unwrap();
+            self.score_sum = self.score_sum.checked_add(score).unwrap();
+            self.score_sq_sum = self.score_sq_sum.checked_add(score * score).unwrap();
+
+            self.decay_to(feedback.created_at, half_life);
+            self.decayed_score_sum = self
+                .decayed_score_sum
+                .checked_add(score as u128 * DECAY_SCALE)
+                .unwrap();
+            self.decayed_weight = self.decayed_weight.checked_add(DECAY_SCALE).unwrap();
+        }
+        self.add_stake(feedback);
+        Ok(())
+    }
+
+    /// Takes feedback that `in_summaries` back out of the aggregates.
+    pub fn remove_feedback(&mut self, feedback: &Feedback, now: i64, half_life: i64) -> Result<()> {
+        self.active_count = self.active_count.checked_sub(1).unwrap();
+
+        if feedback.is_scored() {
+            let score = feedback.score as u64;
+            self.score_count = self.score_count.checked_sub(1).unwrap();
+            self.score_sum = self.score_sum.checked_sub(score).unwrap();
+            self.score_sq_sum = self.score_sq_sum.checked_sub(score * score).unwrap();
+
+            // `decay_to` rounds the sums down, so once this is the last scored
+            // feedback its own decayed weight can exceed them by a few units.
+            let weight = decay_factor(now - feedback.created_at, half_life);
+            self.decay_to(now, half_life);
+            self.decayed_score_sum -= (score as u128 * weight).min(self.decayed_score_sum);
+            self.decayed_weight -= weight.min(self.decayed_weight);
+        }
+        self.remove_stake(feedback);
+        Ok(())
+    }
+
+    /// Appends `client` to the agent's last `[b"clients", agent, page]` page,
+    /// which is looked up in `remaining` and created on first use.
+    pub fn add_client<'info>(
+        &mut self,
+        remaining: &[AccountInfo<'info>],
+        client: &Pubkey,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let page = (self.client_count / CLIENTS_PER_PAGE as u64) as u32;
+        let seeds: &[&[u8]] = &[b"clients", self.agent.as_ref(), &page.to_le_bytes()];
+        let (pda, _) = Pubkey::find_program_address(seeds, &crate::ID);
+        let info = remaining
+            .iter()
+            .find(|a| a.key() == pda)
+            .ok_or(E8004::ClientPageMissing)?;
+
+        let (existing, bump) = load_or_create_pda::<ClientPage>(
+            info,
+            seeds,
+            ClientPage::SPACE,
+            payer,
+            system_program,
+        )?;
+        let mut client_page = existing.unwrap_or(ClientPage {
+            agent: self.agent,
+            page,
+            bump,
+            ..Default::default()
+        });
+        client_page.clients.push(*client);
+        store_account(&client_page, info)?;
+
+        self.client_count = self.client_count.checked_add(1).unwrap();
+        Ok(())
+    }
+
+    /// Replaces the contribution of `old` with that of its amendment `new`.
+    /// The feedback keeps its age, so its decayed weight carries over and
+    /// `feedback_count` is unchanged.
+    pub fn amend_feedback(
+        &mut self,
+        old: &Feedback,
+        new: &Feedback,
+        now: i64,
+        half_life: i64,
+    ) -> Result<()> {
+        self.remove_feedback(old, now, half_life)?;
+
+        self.active_count = self.active_count.checked_add(1).unwrap();
+        if new.is_scored() {
+            let score = new.score as u64;
+            self.score_count = self.score_count.checked_add(1).unwrap();
+            self.score_sum = self.score_sum.checked_add(score).unwrap();
+            self.score_sq_sum = self.score_sq_sum.checked_add(score * score).unwrap();
+
+            let weight = decay_factor(now - new.created_at, half_life);
+            self.decayed_score_sum = self
+                .decayed_score_sum
+                .checked_add(score as u128 * weight)
+                .unwrap();
+            self.decayed_weight = self.decayed_weight.checked_add(weight).unwrap();
+        }
+        self.add_stake(new);
+        Ok(())
+    }
+
+    /// Stake only weights scored feedback; value feedback has no common scale.
+    pub fn add_stake(&mut self, feedback: &Feedback) {
+        if !feedback.is_scored() || feedback.stake == 0 {
+            return;
+        }
+        let stake = feedback.stake as u128;
+        self.stake_total = self.stake_total.checked_add(stake).unwrap();
+        self.stake_weighted_score_sum = self
+            .stake_weighted_score_sum
+            .checked_add(stake * feedback.score as u128)
+            .unwrap();
+    }
+
+    pub fn remove_stake(&mut self, feedback: &Feedback) {
+        if !feedback.is_scored() || feedback.stake == 0 {
+            return;
+        }
+        let stake = feedback.stake as u128;
+        self.stake_total = self.stake_total.checked_sub(stake).unwrap();
+        self.stake_weighted_score_sum = self
+            .stake_weighted_score_sum
+            .checked_sub(stake * feedback.score as u128)
+            .unwrap();
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct TagSummary {
+    pub agent: Pubkey,
+    pub tag: [u8; 32],
+    pub feedback_count: u64,
+    pub active_count: u64,
+    pub score_sum: u64,
+    pub bump: u8,
+    pub score_count: u64,
+    /// Sum of the tag's value feedback in `VALUE_DECIMALS` fixed point. Values
+    /// are only comparable within a tag, so the agent summary has none.
+    pub value_sum: i128,
+    pub value_count: u64,
+}
+
+impl TagSummary {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 16 + 8;
+
+    pub fn add_feedback(&mut self, feedback: &Feedback) -> Result<()> {
+        self.feedback_count = self.feedback_count.checked_add(1).unwrap();
+        self.active_count = self.active_count.checked_add(1).unwrap();
+        if feedback.is_scored() {
+            self.score_count = self.score_count.checked_add(1).unwrap();
+            self.score_sum = self.score_sum.checked_add(feedback.score as u64).unwrap();
+        } else {
+            self.value_count = self.value_count.checked_add(1).unwrap();
+            self.value_sum = self
+                .value_sum
+                .checked_add(feedback.normalized_value()?)
+                .ok_or(E8004::InvalidValue)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_feedback(&mut self, feedback: &Feedback) -> Result<()> {
+        self.active_count = self.active_count.checked_sub(1).unwrap();
+        if feedback.is_scored() {
+            self.score_count = self.score_count.checked_sub(1).unwrap();
+            self.score_sum = self.score_sum.checked_sub(feedback.score as u64).unwrap();
+        } else {
+            self.value_count = self.value_count.checked_sub(1).unwrap();
+            self.value_sum = self
+                .value_sum
+                .checked_sub(feedback.normalized_value()?)
+                .ok_or(E8004::InvalidValue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StakeWeightedScore {
+    pub stake_total: u128,
+    pub average_score: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DecayedScore {
+    pub weight: u128,
+    pub average_score: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeedbackSummary {
+    pub count: u64,
+    pub average_score: u8,
+    /// Mean of the matching value feedback in `VALUE_DECIMALS` fixed point.
+    pub average_value: i128,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TagScore {
+    pub tag: [u8; 32],
+    pub count: u64,
+    pub average_score: u8,
+    pub average_value: i128,
+}
+
+/// Loads the `[b"tsum", agent, tag]` summary from `remaining`, applies `f` and
+/// writes it back. The zero tag means "untagged" and is never aggregated.
+/// With a payer the summary is created on first use; without one an empty
+/// summary is skipped since it never counted anything.
+fn update_tag_summary<'info>(
+    remaining: &[AccountInfo<'info>],
+    agent: &Pubkey,
+    tag: &[u8; 32],
+    payer: Option<(&AccountInfo<'info>, &AccountInfo<'info>)>,
+    f: impl FnOnce(&mut TagSummary) -> Result<()>,
+) -> Result<()> {
+    if *tag == [0u8; 32] {
+        return Ok(());
+    }
+
+    let (pda, bump) =
+        Pubkey::find_program_address(&[b"tsum", agent.as_ref(), tag.as_ref()], &crate::ID);
+    let info = remaining
+        .iter()
+        .find(|a| a.key() == pda)
+        .ok_or(E8004::TagSummaryMissing)?;
+    require!(info.is_writable, E8004::TagSummaryMissing);
+
+    let mut summary = if info.data_is_empty() {
+        let Some((payer, system_program)) = payer else {
+            return Ok(());
+        };
+        create_pda_account(
+            payer,
+            info,
+            system_program,
+            TagSummary::SPACE,
+            &[b"tsum", agent.as_ref(), tag.as_ref(), &[bump]],
+        )?;
+        TagSummary {
+            agent: *agent,
+            tag: *tag,
+            bump,
+            ..Default::default()
+        }
+    } else {
+        require!(info.owner == &crate::ID, E8004::TagSummaryMissing);
+        TagSummary::try_deserialize(&mut &info.try_borrow_data()?[..])?
+    };
+
+    f(&mut summary)?;
+    store_account(&summary, info)
+}
+
+/// Applies `f` once per distinct non-zero tag of a feedback.
+pub(crate) fn update_feedback_tags<'info>(
+    remaining: &[AccountInfo<'info>],
+    agent: &Pubkey,
+    tag1: &[u8; 32],
+    tag2: &[u8; 32],
+    payer: Option<(&AccountInfo<'info>, &AccountInfo<'info>)>,
+    f: impl Fn(&mut TagSummary) -> Result<()>,
+) -> Result<()> {
+    update_tag_summary(remaining, agent, tag1, payer, &f)?;
+    if tag2 != tag1 {
+        update_tag_summary(remaining, agent, tag2, payer, &f)?;
+    }
+    Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(expected_index: u64)]
 pub struct GiveFeedbackCtx<'info> {
     pub agent: Account<'info, Agent>,
 
@@ -38,7 +546,7 @@ pub struct GiveFeedbackCtx<'info> {
     #[account(
         init_if_needed,
         payer = client,
-        space = 8 + 32 + 32 + 8 + 1,
+        space = ClientIndex::SPACE,
         seeds=[b"idx", agent.key().as_ref(), client.key().as_ref()],
         bump
     )]
@@ -47,20 +555,41 @@ pub struct GiveFeedbackCtx<'info> {
     #[account(
         init,
         payer = client,
-        space = 8 + 32 + 32 + 8 + 1 + 32 + 32 + 1 + 4 + MAX_FILE_URI + 32 + 1,
-        seeds=[b"fb", agent.key().as_ref(), client.key().as_ref(), &expected_index.to_le_bytes()],
+        space = Feedback::SPACE,
+        seeds=[
+            b"fb",
+            agent.key().as_ref(),
+            client.key().as_ref(),
+            &(idx.last_index + 1).to_le_bytes()
+        ],
         bump
     )]
     pub feedback: Account<'info, Feedback>,
 
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = ReputationSummary::SPACE,
+        seeds=[b"rsum", agent.key().as_ref()],
+        bump
+    )]
+    pub summary: Account<'info, ReputationSummary>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
     #[account(address = sysvar::instructions::ID)]
     pub ix_sysvar: AccountInfo<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
-pub fn give_feedback_ed25519(
-    mut ctx: Context<GiveFeedbackCtx>,
+/// Tag summaries go in remaining accounts, and so does the agent's current
+/// `ClientPage` when this is the client's first feedback to the agent.
+#[allow(clippy::too_many_arguments)]
+pub fn give_feedback_ed25519<'info>(
+    ctx: Context<'_, '_, '_, 'info, GiveFeedbackCtx<'info>>,
     score: u8,
     tag1: [u8; 32],
     tag2: [u8; 32],
@@ -69,45 +598,150 @@ pub fn give_feedback_ed25519(
     auth_struct_bytes: Vec<u8>,
     signature: Vec<u8>,
     expected_index: u64,
+    tag1_label: Option<String>,
+    tag2_label: Option<String>,
+    payload: Option<Vec<u8>>,
 ) -> Result<()> {
     require!(score <= 100, E8004::InvalidScore);
-    require!(
-        file_uri.as_bytes().len() <= MAX_FILE_URI,
-        E8004::Unauthorized
-    );
+    verify_tag_label(&tag1, &tag1_label)?;
+    verify_tag_label(&tag2, &tag2_label)?;
+
+    record_feedback(
+        ctx,
+        FEEDBACK_VERSION_SCORE,
+        score,
+        0,
+        0,
+        tag1,
+        tag2,
+        tag1_label,
+        tag2_label,
+        file_uri,
+        file_hash,
+        auth_struct_bytes,
+        signature,
+        expected_index,
+        payload,
+    )
+}
+
+/// Signed fixed-point variant of `give_feedback_ed25519`: `value` is read
+/// with `value_decimals` decimals (at most `VALUE_DECIMALS`) and may be at
+/// most `MAX_VALUE` in magnitude once normalized. Values only count towards
+/// the summaries of their tags.
+#[allow(clippy::too_many_arguments)]
+pub fn give_feedback_value_ed25519<'info>(
+    ctx: Context<'_, '_, '_, 'info, GiveFeedbackCtx<'info>>,
+    value: i128,
+    value_decimals: u8,
+    tag1: [u8; 32],
+    tag2: [u8; 32],
+    file_uri: String,
+    file_hash: [u8; 32],
+    auth_struct_bytes: Vec<u8>,
+    signature: Vec<u8>,
+    expected_index: u64,
+    tag1_label: Option<String>,
+    tag2_label: Option<String>,
+    payload: Option<Vec<u8>>,
+) -> Result<()> {
+    normalize_value(value, value_decimals)?;
+    verify_tag_label(&tag1, &tag1_label)?;
+    verify_tag_label(&tag2, &tag2_label)?;
+
+    record_feedback(
+        ctx,
+        FEEDBACK_VERSION_VALUE,
+        0,
+        value,
+        value_decimals,
+        tag1,
+        tag2,
+        tag1_label,
+        tag2_label,
+        file_uri,
+        file_hash,
+        auth_struct_bytes,
+        signature,
+        expected_index,
+        payload,
+    )
+}
+
+/// Checks that `auth_struct_bytes` is a FeedbackAuth for `agent` and `client`,
+/// unexpired, and signed by the agent owner in an ed25519 instruction of the
+/// current transaction.
+pub(crate) fn verify_feedback_auth(
+    agent: &Agent,
+    client: &Pubkey,
+    auth_struct_bytes: Vec<u8>,
+    signature: &[u8],
+    ix_sysvar: &AccountInfo,
+    now: i64,
+) -> Result<FeedbackAuth> {
     require!(signature.len() == 64, E8004::BadSignature);
+    require!(*client != agent.owner, E8004::SelfFeedback);
+
+    let auth_struct = decode_auth_struct(auth_struct_bytes)?;
+    let digest = keccak_auth_struct(&auth_struct);
+    require!(auth_struct.agent_id == agent.id, E8004::Unauthorized);
+    require!(auth_struct.client == *client, E8004::Unauthorized);
+
+    require!((now as u64) < auth_struct.expiry, E8004::ExpiredAuth);
+    require!(auth_struct.signer == agent.owner, E8004::Unauthorized);
+
+    let signer_ok = verify_ed25519(&digest, signature, &auth_struct.signer, ix_sysvar);
+    require!(signer_ok, E8004::BadSignature);
+
+    Ok(auth_struct)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_feedback<'info>(
+    mut ctx: Context<'_, '_, '_, 'info, GiveFeedbackCtx<'info>>,
+    version: u8,
+    score: u8,
+    value: i128,
+    value_decimals: u8,
+    tag1: [u8; 32],
+    tag2: [u8; 32],
+    tag1_label: Option<String>,
+    tag2_label: Option<String>,
+    file_uri: String,
+    file_hash: [u8; 32],
+    auth_struct_bytes: Vec<u8>,
+    signature: Vec<u8>,
+    expected_index: u64,
+    payload: Option<Vec<u8>>,
+) -> Result<()> {
+    require!(file_uri.len() <= MAX_FILE_URI, E8004::Unauthorized);
+    if let Some(payload) = &payload {
+        require!(payload.len() <= MAX_INLINE_PAYLOAD, E8004::PayloadTooLarge);
+        require!(keccak_bytes(payload) == file_hash, E8004::PayloadMismatch);
+    }
 
     let idx_bump = ctx.bumps.idx;
     let feedback_bump = ctx.bumps.feedback;
+    let summary_bump = ctx.bumps.summary;
     let accounts = &mut ctx.accounts;
 
     let agent_key = accounts.agent.key();
-    let agent_owner = accounts.agent.owner;
-    let agent_id = accounts.agent.id;
     let client_key = accounts.client.key();
+    let clock = Clock::get()?;
 
-    require!(client_key != agent_owner, E8004::SelfFeedback);
-
-    let auth_struct = decode_auth_struct(auth_struct_bytes)?;
-    let digest = keccak_auth_struct(&auth_struct);
-    require!(auth_struct.agent_id == agent_id, E8004::Unauthorized);
-    require!(auth_struct.client == client_key, E8004::Unauthorized);
-
-    let current_ts = Clock::get()?.unix_timestamp as u64;
-    require!(current_ts < auth_struct.expiry, E8004::ExpiredAuth);
-    require!(auth_struct.signer == agent_owner, E8004::Unauthorized);
-
-    let signer_ok = verify_ed25519(
-        &digest,
-        signature.as_slice(),
-        &auth_struct.signer,
+    let auth_struct = verify_feedback_auth(
+        &accounts.agent,
+        &client_key,
+        auth_struct_bytes,
+        &signature,
         &accounts.ix_sysvar,
-    );
-    require!(signer_ok, E8004::BadSignature);
+        clock.unix_timestamp,
+    )?;
 
+    let new_client = accounts.idx.last_index == 0;
     let next_index = {
         let idx = &mut accounts.idx;
-        if idx.last_index == 0 {
+        if new_client {
             idx.agent = agent_key;
             idx.client = client_key;
             idx.bump = idx_bump;
@@ -130,15 +764,278 @@ pub fn give_feedback_ed25519(
     feedback.file_uri = file_uri;
     feedback.file_hash = file_hash;
     feedback.bump = feedback_bump;
+    feedback.counted = true;
+    feedback.created_at = clock.unix_timestamp;
+    feedback.created_slot = clock.slot;
+    feedback.revoked_at = 0;
+    feedback.revoked_slot = 0;
+    feedback.version = version;
+    feedback.value = value;
+    feedback.value_decimals = value_decimals;
+    feedback.stake = 0;
+    feedback.struck = false;
+    feedback.revision = 0;
+    feedback.prev_hash = [0u8; 32];
+    feedback.amended_at = 0;
+
+    let summary = &mut accounts.summary;
+    if summary.agent == Pubkey::default() {
+        summary.agent = agent_key;
+        summary.bump = summary_bump;
+    }
+    let half_life = ReputationConfig::load(&accounts.config)?.half_life;
+    summary.add_feedback(feedback, half_life)?;
+
+    let client_info = accounts.client.to_account_info();
+    let system_info = accounts.system_program.to_account_info();
+    if new_client {
+        summary.add_client(
+            ctx.remaining_accounts,
+            &client_key,
+            &client_info,
+            &system_info,
+        )?;
+    }
+
+    update_feedback_tags(
+        ctx.remaining_accounts,
+        &agent_key,
+        &tag1,
+        &tag2,
+        Some((&client_info, &system_info)),
+        |t| t.add_feedback(feedback),
+    )?;
 
     emit!(NewFeedback {
         agent: feedback.agent,
         client: feedback.client,
-        score,
+        index: feedback.index,
+        score: feedback.score,
         tag1,
-        tag2
+        tag2,
+        tag1_label,
+        tag2_label,
+        created_at: feedback.created_at,
+        created_slot: feedback.created_slot,
+        version,
+        value,
+        value_decimals
     });
 
+    if let Some(payload) = payload {
+        emit!(FeedbackPayload {
+            agent: feedback.agent,
+            client: feedback.client,
+            index: feedback.index,
+            file_hash,
+            payload
+        });
+    }
+
+    Ok(())
+}
+
+/// The agent summary `feedback` was counted in. It is optional in contexts
+/// that touch existing feedback so feedback given before the summary existed
+/// can still be revoked, but must be passed whenever the feedback was counted.
+pub(crate) fn counted_summary<'a, 'info>(
+    feedback: &Feedback,
+    summary: &'a mut Option<Account<'info, ReputationSummary>>,
+) -> Result<Option<&'a mut Account<'info, ReputationSummary>>> {
+    if !feedback.counted {
+        return Ok(None);
+    }
+    match summary {
+        Some(summary) => Ok(Some(summary)),
+        None => err!(E8004::SummaryMissing),
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeedbackEntry {
+    pub score: u8,
+    pub tag1: [u8; 32],
+    pub tag2: [u8; 32],
+    pub file_uri: String,
+    pub file_hash: [u8; 32],
+    pub auth_struct_bytes: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub expected_index: u64,
+}
+
+#[derive(Accounts)]
+pub struct GiveFeedbackBatchCtx<'info> {
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(address = sysvar::instructions::ID)]
+    pub ix_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Gives feedback to several agents in one transaction. Each entry needs its
+/// own ed25519 instruction and consumes four remaining accounts, in entry
+/// order: `agent`, `idx`, `feedback` and `summary`. Tag summaries for all
+/// entries, and the current client page of each agent the client is new to,
+/// follow after those in any order.
+pub fn give_feedback_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, GiveFeedbackBatchCtx<'info>>,
+    entries: Vec<FeedbackEntry>,
+) -> Result<()> {
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_FEEDBACK_BATCH,
+        E8004::InvalidBatch
+    );
+    require!(
+        ctx.remaining_accounts.len() >= entries.len() * 4,
+        E8004::InvalidBatch
+    );
+
+    let accounts = &ctx.accounts;
+    let client_info = accounts.client.to_account_info();
+    let system_info = accounts.system_program.to_account_info();
+    let client_key = client_info.key();
+    let half_life = ReputationConfig::load(&accounts.config)?.half_life;
+    let clock = Clock::get()?;
+
+    for (i, entry) in entries.into_iter().enumerate() {
+        let [agent_info, idx_info, feedback_info, summary_info] =
+            &ctx.remaining_accounts[i * 4..i * 4 + 4]
+        else {
+            return err!(E8004::InvalidBatch);
+        };
+
+        require!(entry.score <= 100, E8004::InvalidScore);
+        require!(entry.file_uri.len() <= MAX_FILE_URI, E8004::Unauthorized);
+
+        require!(agent_info.owner == &crate::ID, E8004::AgentNotFound);
+        let agent = Agent::try_deserialize(&mut &agent_info.try_borrow_data()?[..])?;
+        let agent_key = agent_info.key();
+
+        let auth_struct = verify_feedback_auth(
+            &agent,
+            &client_key,
+            entry.auth_struct_bytes,
+            &entry.signature,
+            &accounts.ix_sysvar,
+            clock.unix_timestamp,
+        )?;
+
+        let (idx, idx_bump) = load_or_create_pda::<ClientIndex>(
+            idx_info,
+            &[b"idx", agent_key.as_ref(), client_key.as_ref()],
+            ClientIndex::SPACE,
+            &client_info,
+            &system_info,
+        )?;
+        let mut idx = idx.unwrap_or(ClientIndex {
+            agent: agent_key,
+            client: client_key,
+            last_index: 0,
+            bump: idx_bump,
+        });
+        let new_client = idx.last_index == 0;
+        let next_index = idx.last_index.checked_add(1).unwrap();
+        require!(entry.expected_index == next_index, E8004::IndexLimit);
+        require!(next_index <= auth_struct.index_limit, E8004::IndexLimit);
+        idx.last_index = next_index;
+        store_account(&idx, idx_info)?;
+
+        let (existing, feedback_bump) = load_or_create_pda::<Feedback>(
+            feedback_info,
+            &[
+                b"fb",
+                agent_key.as_ref(),
+                client_key.as_ref(),
+                &next_index.to_le_bytes(),
+            ],
+            Feedback::SPACE,
+            &client_info,
+            &system_info,
+        )?;
+        require!(existing.is_none(), E8004::IndexLimit);
+
+        let feedback = Feedback {
+            agent: agent_key,
+            client: client_key,
+            index: next_index,
+            score: entry.score,
+            tag1: entry.tag1,
+            tag2: entry.tag2,
+            revoked: false,
+            file_uri: entry.file_uri,
+            file_hash: entry.file_hash,
+            bump: feedback_bump,
+            counted: true,
+            created_at: clock.unix_timestamp,
+            created_slot: clock.slot,
+            revoked_at: 0,
+            revoked_slot: 0,
+            version: FEEDBACK_VERSION_SCORE,
+            value: 0,
+            value_decimals: 0,
+            stake: 0,
+            struck: false,
+            revision: 0,
+            prev_hash: [0u8; 32],
+            amended_at: 0,
+        };
+        store_account(&feedback, feedback_info)?;
+
+        let (summary, summary_bump) = load_or_create_pda::<ReputationSummary>(
+            summary_info,
+            &[b"rsum", agent_key.as_ref()],
+            ReputationSummary::SPACE,
+            &client_info,
+            &system_info,
+        )?;
+        let mut summary = summary.unwrap_or(ReputationSummary {
+            agent: agent_key,
+            bump: summary_bump,
+            ..Default::default()
+        });
+        summary.add_feedback(&feedback, half_life)?;
+        if new_client {
+            summary.add_client(
+                ctx.remaining_accounts,
+                &client_key,
+                &client_info,
+                &system_info,
+            )?;
+        }
+        store_account(&summary, summary_info)?;
+
+        update_feedback_tags(
+            ctx.remaining_accounts,
+            &agent_key,
+            &feedback.tag1,
+            &feedback.tag2,
+            Some((&client_info, &system_info)),
+            |t| t.add_feedback(&feedback),
+        )?;
+
+        emit!(NewFeedback {
+            agent: feedback.agent,
+            client: feedback.client,
+            index: feedback.index,
+            score: feedback.score,
+            tag1: feedback.tag1,
+            tag2: feedback.tag2,
+            tag1_label: None,
+            tag2_label: None,
+            created_at: feedback.created_at,
+            created_slot: feedback.created_slot,
+            version: feedback.version,
+            value: feedback.value,
+            value_decimals: feedback.value_decimals
+        });
+    }
+
     Ok(())
 }
 
@@ -146,6 +1043,18 @@ pub fn give_feedback_ed25519(
 pub struct RevokeFeedbackCtx<'info> {
     #[account(mut)]
     pub feedback: Account<'info, Feedback>,
+
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Option<Account<'info, ReputationSummary>>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
     pub client: Signer<'info>,
 }
 
@@ -157,24 +1066,582 @@ pub fn revoke_feedback(mut ctx: Context<RevokeFeedbackCtx>, _index: u64) -> Resu
     require!(feedback.client == client_key, E8004::Unauthorized);
     require!(!feedback.revoked, E8004::Unauthorized);
 
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    // Struck feedback already left the summaries when the dispute resolved.
+    let was_counted = feedback.in_summaries();
     feedback.revoked = true;
+    feedback.revoked_at = now;
+    feedback.revoked_slot = clock.slot;
+
+    if let Some(summary) = counted_summary(feedback, &mut accounts.summary)? {
+        summary.revoked_count = summary.revoked_count.checked_add(1).unwrap();
+        if was_counted {
+            let half_life = ReputationConfig::load(&accounts.config)?.half_life;
+            summary.remove_feedback(feedback, now, half_life)?;
+
+            update_feedback_tags(
+                ctx.remaining_accounts,
+                &feedback.agent,
+                &feedback.tag1,
+                &feedback.tag2,
+                None,
+                |t| t.remove_feedback(feedback),
+            )?;
+        }
+    }
 
     emit!(FeedbackRevoked {
         agent: feedback.agent,
         client: feedback.client,
-        index: feedback.index
+        index: feedback.index,
+        revoked_at: feedback.revoked_at,
+        revoked_slot: feedback.revoked_slot
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AmendFeedbackCtx<'info> {
+    #[account(
+        mut,
+        has_one = client,
+        constraint = feedback.is_active() @ E8004::Unauthorized
+    )]
+    pub feedback: Account<'info, Feedback>,
+
+    /// CHECK: the feedback's dispute PDA, which usually does not exist; the
+    /// arbiter rules on the feedback as it was disputed.
+    #[account(seeds=[b"dispute", feedback.key().as_ref()], bump)]
+    pub dispute: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Option<Account<'info, ReputationSummary>>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Corrects active scored feedback in place without consuming a new index.
+/// Summaries of both the old and the new tags go in remaining accounts; new
+/// tag summaries are created at the client's expense. Feedback under an open
+/// dispute cannot be amended.
+#[allow(clippy::too_many_arguments)]
+pub fn amend_feedback<'info>(
+    ctx: Context<'_, '_, '_, 'info, AmendFeedbackCtx<'info>>,
+    score: u8,
+    tag1: [u8; 32],
+    tag2: [u8; 32],
+    file_uri: String,
+    file_hash: [u8; 32],
+    tag1_label: Option<String>,
+    tag2_label: Option<String>,
+) -> Result<()> {
+    require!(score <= 100, E8004::InvalidScore);
+    require!(file_uri.len() <= MAX_FILE_URI, E8004::Unauthorized);
+    verify_tag_label(&tag1, &tag1_label)?;
+    verify_tag_label(&tag2, &tag2_label)?;
+
+    let accounts = ctx.accounts;
+    require!(!dispute_is_open(&accounts.dispute)?, E8004::DisputeOpen);
+    let feedback = &mut accounts.feedback;
+    require!(feedback.is_scored(), E8004::InvalidScore);
+
+    let now = Clock::get()?.unix_timestamp;
+    let old: Feedback = (**feedback).clone();
+    feedback.prev_hash = old.content_hash();
+    feedback.revision = old.revision.checked_add(1).unwrap();
+    feedback.amended_at = now;
+    feedback.score = score;
+    feedback.tag1 = tag1;
+    feedback.tag2 = tag2;
+    feedback.file_uri = file_uri;
+    feedback.file_hash = file_hash;
+
+    let half_life = ReputationConfig::load(&accounts.config)?.half_life;
+    if let Some(summary) = counted_summary(&old, &mut accounts.summary)? {
+        summary.amend_feedback(&old, feedback, now, half_life)?;
+
+        let client = accounts.client.to_account_info();
+        let system_program = accounts.system_program.to_account_info();
+        let mut tags = vec![old.tag1, old.tag2, tag1, tag2];
+        tags.sort();
+        tags.dedup();
+        for tag in tags {
+            let in_old = tag == old.tag1 || tag == old.tag2;
+            let in_new = tag == tag1 || tag == tag2;
+            // Only tags the feedback moves to need creating.
+            let payer = in_new.then_some((&client, &system_program));
+            update_tag_summary(ctx.remaining_accounts, &feedback.agent, &tag, payer, |t| {
+                if in_old {
+                    t.remove_feedback(&old)?;
+                }
+                if in_new {
+                    t.add_feedback(feedback)?;
+                }
+                if in_old && in_new {
+                    t.feedback_count = t.feedback_count.checked_sub(1).unwrap();
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    emit!(FeedbackAmended {
+        agent: feedback.agent,
+        client: feedback.client,
+        index: feedback.index,
+        revision: feedback.revision,
+        score,
+        tag1,
+        tag2,
+        tag1_label,
+        tag2_label,
+        file_hash,
+        prev_hash: feedback.prev_hash,
+        amended_at: now
     });
 
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct CloseFeedbackCtx<'info> {
+    #[account(
+        mut,
+        has_one = client,
+        close = client,
+        constraint = feedback.revoked @ E8004::NotRevoked,
+        constraint = feedback.stake == 0 @ E8004::StakeLocked
+    )]
+    pub feedback: Account<'info, Feedback>,
+
+    /// CHECK: the feedback's dispute PDA, which usually does not exist; an
+    /// open dispute keeps the feedback around for the arbiter.
+    #[account(seeds=[b"dispute", feedback.key().as_ref()], bump)]
+    pub dispute: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = client,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds=[
+            b"fbt",
+            feedback.agent.as_ref(),
+            client.key().as_ref(),
+            &feedback.index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub tombstone: Account<'info, FeedbackTombstone>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Closes revoked feedback to the client. Revocation already removed it from
+/// every summary, so only the tombstone is needed to keep the index on record.
+/// Any stake has to be withdrawn first, or its escrow would be stranded, and
+/// an open dispute has to be resolved.
+pub fn close_feedback(mut ctx: Context<CloseFeedbackCtx>) -> Result<()> {
+    let tombstone_bump = ctx.bumps.tombstone;
+    let accounts = &mut ctx.accounts;
+    require!(!dispute_is_open(&accounts.dispute)?, E8004::DisputeOpen);
+    let feedback = &accounts.feedback;
+
+    let tombstone = &mut accounts.tombstone;
+    tombstone.agent = feedback.agent;
+    tombstone.client = feedback.client;
+    tombstone.index = feedback.index;
+    tombstone.revoked_at = feedback.revoked_at;
+    tombstone.closed_at = Clock::get()?.unix_timestamp;
+    tombstone.bump = tombstone_bump;
+
+    emit!(FeedbackClosed {
+        agent: tombstone.agent,
+        client: tombstone.client,
+        index: tombstone.index,
+        tombstone: tombstone.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateFeedbackCtx<'info> {
+    /// CHECK: may predate the current `Feedback` layout and fail to
+    /// deserialize; owner and discriminator are checked in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub feedback: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a `Feedback` account allocated under an older layout to
+/// `Feedback::SPACE`. The appended bytes are zeroed, so timestamps of migrated
+/// feedback read as 0 ("recorded before timestamps existed") and it is not
+/// `counted` in the summaries.
+pub fn migrate_feedback(mut ctx: Context<MigrateFeedbackCtx>) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let info = accounts.feedback.to_account_info();
+
+    require!(
+        info.try_borrow_data()?
+            .starts_with(&Feedback::DISCRIMINATOR),
+        E8004::Unauthorized
+    );
+    let old_len = info.data_len();
+    require!(old_len < Feedback::SPACE, E8004::AlreadyMigrated);
+
+    let shortfall = Rent::get()?
+        .minimum_balance(Feedback::SPACE)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.realloc(Feedback::SPACE, true)?;
+
+    emit!(FeedbackMigrated {
+        feedback: info.key(),
+        old_len: old_len as u32,
+        new_len: Feedback::SPACE as u32
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetReputationConfigCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 8 + 1 + 1 + 32 + 8 + 32,
+        seeds=[b"rcfg"],
+        bump
+    )]
+    pub config: Account<'info, ReputationConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_reputation_config(
+    mut ctx: Context<SetReputationConfigCtx>,
+    half_life: i64,
+    stake_mint: Option<Pubkey>,
+    stake_cooldown: i64,
+    arbiter: Pubkey,
+) -> Result<()> {
+    require!(half_life > 0, E8004::InvalidConfig);
+    require!(stake_cooldown >= 0, E8004::InvalidConfig);
+
+    let config_bump = ctx.bumps.config;
+    let accounts = &mut ctx.accounts;
+    let config = &mut accounts.config;
+    config.half_life = half_life;
+    config.bump = config_bump;
+    config.stake_mint = stake_mint;
+    config.stake_cooldown = stake_cooldown;
+    config.arbiter = arbiter;
+
+    emit!(ReputationConfigSet {
+        half_life,
+        stake_mint,
+        stake_cooldown,
+        arbiter
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetDecayedScoreCtx<'info> {
+    #[account(seeds=[b"rsum", summary.agent.as_ref()], bump = summary.bump)]
+    pub summary: Account<'info, ReputationSummary>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+/// Returns the agent's time-decayed reputation as of now. `weight` is the
+/// decayed number of reviews in `DECAY_SCALE` fixed point.
+pub fn get_decayed_score(ctx: Context<GetDecayedScoreCtx>) -> Result<DecayedScore> {
+    let half_life = ReputationConfig::load(&ctx.accounts.config)?.half_life;
+    let mut summary = (*ctx.accounts.summary).clone();
+    summary.decay_to(Clock::get()?.unix_timestamp, half_life);
+
+    Ok(DecayedScore {
+        weight: summary.decayed_weight,
+        average_score: summary
+            .decayed_score_sum
+            .checked_div(summary.decayed_weight)
+            .unwrap_or(0) as u8,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetStakeWeightedScoreCtx<'info> {
+    #[account(seeds=[b"rsum", summary.agent.as_ref()], bump = summary.bump)]
+    pub summary: Account<'info, ReputationSummary>,
+}
+
+pub fn get_stake_weighted_score(
+    ctx: Context<GetStakeWeightedScoreCtx>,
+) -> Result<StakeWeightedScore> {
+    let summary = &ctx.accounts.summary;
+
+    Ok(StakeWeightedScore {
+        stake_total: summary.stake_total,
+        average_score: summary
+            .stake_weighted_score_sum
+            .checked_div(summary.stake_total)
+            .unwrap_or(0) as u8,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetTagSummariesCtx<'info> {
+    pub agent: Account<'info, Agent>,
+}
+
+/// Returns one `TagScore` per requested tag. The `[b"tsum", agent, tag]`
+/// accounts are passed as remaining accounts; tags that were never used have
+/// no account yet and report a zero count.
+pub fn get_tag_summaries(
+    ctx: Context<GetTagSummariesCtx>,
+    tags: Vec<[u8; 32]>,
+) -> Result<Vec<TagScore>> {
+    require!(tags.len() <= MAX_TAG_QUERY, E8004::TooManyTags);
+    let agent_key = ctx.accounts.agent.key();
+
+    let mut out = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let (pda, _) =
+            Pubkey::find_program_address(&[b"tsum", agent_key.as_ref(), tag.as_ref()], &crate::ID);
+        let info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|a| a.key() == pda)
+            .ok_or(E8004::TagSummaryMissing)?;
+
+        if info.data_is_empty() {
+            out.push(TagScore {
+                tag,
+                count: 0,
+                average_score: 0,
+                average_value: 0,
+            });
+            continue;
+        }
+
+        require!(info.owner == &crate::ID, E8004::TagSummaryMissing);
+        let summary = TagSummary::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        out.push(TagScore {
+            tag,
+            count: summary.active_count,
+            average_score: summary
+                .score_sum
+                .checked_div(summary.score_count)
+                .unwrap_or(0) as u8,
+            average_value: summary
+                .value_sum
+                .checked_div(summary.value_count as i128)
+                .unwrap_or(0),
+        });
+    }
+
+    Ok(out)
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct GetClientsCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        seeds=[b"clients", agent.key().as_ref(), &page.to_le_bytes()],
+        bump = client_page.bump
+    )]
+    pub client_page: Account<'info, ClientPage>,
+}
+
+/// Returns one page of the agent's clients; pages are numbered from 0 and
+/// `ReputationSummary.client_count` tells how many exist.
+pub fn get_clients(ctx: Context<GetClientsCtx>, _page: u32) -> Result<Vec<Pubkey>> {
+    Ok(ctx.accounts.client_page.clients.clone())
+}
+
+#[derive(Accounts)]
+pub struct GetFeedbackSummaryCtx<'info> {
+    pub agent: Account<'info, Agent>,
+}
+
+/// ERC-8004 `getSummary`: aggregates the `Feedback` accounts passed as
+/// remaining accounts. An empty `client_addresses` list accepts every client
+/// and a zero tag matches any tag. Revoked and struck feedback is skipped.
+pub fn get_feedback_summary(
+    ctx: Context<GetFeedbackSummaryCtx>,
+    client_addresses: Vec<Pubkey>,
+    tag1: [u8; 32],
+    tag2: [u8; 32],
+) -> Result<FeedbackSummary> {
+    let agent_key = ctx.accounts.agent.key();
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut count = 0u64;
+    let mut score_count = 0u64;
+    let mut score_sum = 0u64;
+    let mut value_count = 0u64;
+    let mut value_sum = 0i128;
+
+    for info in ctx.remaining_accounts.iter() {
+        require!(!seen.contains(info.key), E8004::DuplicateAccount);
+        seen.push(info.key());
+
+        require!(info.owner == &crate::ID, E8004::Unauthorized);
+        let feedback = Feedback::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(feedback.agent == agent_key, E8004::Unauthorized);
+
+        let pda = Pubkey::create_program_address(
+            &[
+                b"fb",
+                feedback.agent.as_ref(),
+                feedback.client.as_ref(),
+                &feedback.index.to_le_bytes(),
+                &[feedback.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| E8004::Unauthorized)?;
+        require!(pda == info.key(), E8004::Unauthorized);
+
+        if !feedback.is_active() {
+            continue;
+        }
+        if !client_addresses.is_empty() && !client_addresses.contains(&feedback.client) {
+            continue;
+        }
+        if tag1 != [0u8; 32] && feedback.tag1 != tag1 {
+            continue;
+        }
+        if tag2 != [0u8; 32] && feedback.tag2 != tag2 {
+            continue;
+        }
+
+        count += 1;
+        if feedback.is_scored() {
+            score_count += 1;
+            score_sum += feedback.score as u64;
+        } else {
+            value_count += 1;
+            value_sum = value_sum
+                .checked_add(feedback.normalized_value()?)
+                .ok_or(E8004::InvalidValue)?;
+        }
+    }
+
+    Ok(FeedbackSummary {
+        count,
+        average_score: score_sum.checked_div(score_count).unwrap_or(0) as u8,
+        average_value: value_sum.checked_div(value_count as i128).unwrap_or(0),
+    })
+}
+
 #[event]
 pub struct NewFeedback {
     pub agent: Pubkey,
     pub client: Pubkey,
+    pub index: u64,
     pub score: u8,
     pub tag1: [u8; 32],
     pub tag2: [u8; 32],
+    pub tag1_label: Option<String>,
+    pub tag2_label: Option<String>,
+    pub created_at: i64,
+    pub created_slot: u64,
+    pub version: u8,
+    pub value: i128,
+    pub value_decimals: u8,
+}
+
+#[event]
+pub struct FeedbackAmended {
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub index: u64,
+    pub revision: u32,
+    pub score: u8,
+    pub tag1: [u8; 32],
+    pub tag2: [u8; 32],
+    pub tag1_label: Option<String>,
+    pub tag2_label: Option<String>,
+    pub file_hash: [u8; 32],
+    pub prev_hash: [u8; 32],
+    pub amended_at: i64,
+}
+
+#[event]
+pub struct FeedbackClosed {
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub index: u64,
+    pub tombstone: Pubkey,
+}
+
+#[event]
+pub struct FeedbackMigrated {
+    pub feedback: Pubkey,
+    pub old_len: u32,
+    pub new_len: u32,
+}
+
+#[event]
+pub struct ReputationConfigSet {
+    pub half_life: i64,
+    pub stake_mint: Option<Pubkey>,
+    pub stake_cooldown: i64,
+    pub arbiter: Pubkey,
+}
+
+/// Emitted when the feedback file was submitted inline; `payload` hashes to
+/// `file_hash`, so indexers can skip resolving `file_uri`.
+#[event]
+pub struct FeedbackPayload {
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub index: u64,
+    pub file_hash: [u8; 32],
+    pub payload: Vec<u8>,
 }
 
 #[event]
@@ -182,4 +1649,6 @@ pub struct FeedbackRevoked {
     pub agent: Pubkey,
     pub client: Pubkey,
     pub index: u64,
+    pub revoked_at: i64,
+    pub revoked_slot: u64,
 }
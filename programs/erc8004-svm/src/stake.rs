@@ -0,0 +1,376 @@
+use crate::dispute::dispute_is_open;
+use crate::identity::Platform;
+use crate::reputation::{counted_summary, Feedback, ReputationConfig, ReputationSummary};
+use crate::E8004;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount};
+
+/// Escrow behind a staked `Feedback`. SOL stakes are held as lamports on this
+/// account; SPL stakes sit in the `[b"fstake_vault", feedback]` token account
+/// it owns.
+#[account]
+pub struct FeedbackStake {
+    pub feedback: Pubkey,
+    pub client: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub staked_at: i64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct StakeFeedbackCtx<'info> {
+    #[account(
+        mut,
+        has_one = client,
+        constraint = feedback.is_active() @ E8004::Unauthorized
+    )]
+    pub feedback: Account<'info, Feedback>,
+
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Option<Account<'info, ReputationSummary>>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = client,
+        space = 8 + 32 + 32 + 1 + 32 + 8 + 8 + 1,
+        seeds=[b"fstake", feedback.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, FeedbackStake>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    // SPL stakes only, when `config.stake_mint` is set.
+    pub stake_mint: Option<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = client,
+        token::mint = stake_mint,
+        token::authority = stake,
+        seeds=[b"fstake_vault", feedback.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn stake_feedback(mut ctx: Context<StakeFeedbackCtx>, amount: u64) -> Result<()> {
+    let stake_bump = ctx.bumps.stake;
+    let accounts = &mut ctx.accounts;
+    require!(amount > 0, E8004::InvalidStake);
+    require!(accounts.feedback.stake == 0, E8004::InvalidStake);
+
+    let mint = ReputationConfig::load(&accounts.config)?.stake_mint;
+    match mint {
+        None => transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.client.to_account_info(),
+                    to: accounts.stake.to_account_info(),
+                },
+            ),
+            amount,
+        )?,
+        Some(mint) => {
+            let (Some(stake_mint), Some(client_token), Some(stake_vault), Some(token_program)) = (
+                &accounts.stake_mint,
+                &accounts.client_token,
+                &accounts.stake_vault,
+                &accounts.token_program,
+            ) else {
+                return err!(E8004::MissingTokenAccounts);
+            };
+            require!(stake_mint.key() == mint, E8004::MintMismatch);
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token::Transfer {
+                        from: client_token.to_account_info(),
+                        to: stake_vault.to_account_info(),
+                        authority: accounts.client.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+
+    let stake = &mut accounts.stake;
+    stake.feedback = accounts.feedback.key();
+    stake.client = accounts.client.key();
+    stake.mint = mint;
+    stake.amount = amount;
+    stake.staked_at = Clock::get()?.unix_timestamp;
+    stake.bump = stake_bump;
+
+    let feedback = &mut accounts.feedback;
+    feedback.stake = amount;
+    if let Some(summary) = counted_summary(feedback, &mut accounts.summary)? {
+        summary.add_stake(feedback);
+    }
+
+    emit!(FeedbackStaked {
+        feedback: stake.feedback,
+        client: stake.client,
+        mint,
+        amount
+    });
+
+    Ok(())
+}
+
+/// Pays `stake.amount` out of escrow to `to` (SOL) or `to_token` (SPL). For
+/// SPL stakes the emptied vault is closed to `rent_to`; the stake account
+/// itself is closed by the calling instruction.
+fn pay_out_stake<'info>(
+    stake: &Account<'info, FeedbackStake>,
+    stake_vault: &Option<Account<'info, TokenAccount>>,
+    token_program: &Option<Program<'info, Token>>,
+    to: &AccountInfo<'info>,
+    to_token: &Option<Account<'info, TokenAccount>>,
+    rent_to: &AccountInfo<'info>,
+) -> Result<()> {
+    if stake.mint.is_none() {
+        let stake_info = stake.to_account_info();
+        **stake_info.try_borrow_mut_lamports()? -= stake.amount;
+        **to.try_borrow_mut_lamports()? += stake.amount;
+        return Ok(());
+    }
+
+    let (Some(stake_vault), Some(to_token), Some(token_program)) =
+        (stake_vault, to_token, token_program)
+    else {
+        return err!(E8004::MissingTokenAccounts);
+    };
+    let seeds: &[&[u8]] = &[b"fstake", stake.feedback.as_ref(), &[stake.bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: stake_vault.to_account_info(),
+                to: to_token.to_account_info(),
+                authority: stake.to_account_info(),
+            },
+            &[seeds],
+        ),
+        stake.amount,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: stake_vault.to_account_info(),
+            destination: rent_to.clone(),
+            authority: stake.to_account_info(),
+        },
+        &[seeds],
+    ))
+}
+
+#[derive(Accounts)]
+pub struct UnstakeFeedbackCtx<'info> {
+    #[account(
+        mut,
+        has_one = client,
+        constraint = !feedback.struck @ E8004::StakeLocked
+    )]
+    pub feedback: Account<'info, Feedback>,
+
+    /// CHECK: the feedback's dispute PDA, which usually does not exist; an
+    /// open dispute keeps the stake locked.
+    #[account(seeds=[b"dispute", feedback.key().as_ref()], bump)]
+    pub dispute: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Option<Account<'info, ReputationSummary>>,
+
+    /// CHECK: the `[b"rcfg"]` config, which may not exist yet.
+    #[account(seeds=[b"rcfg"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"fstake", feedback.key().as_ref()],
+        bump = stake.bump,
+        has_one = feedback,
+        close = client
+    )]
+    pub stake: Account<'info, FeedbackStake>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    #[account(mut, token::mint = stake.mint.unwrap_or_default(), token::authority = client)]
+    pub client_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"fstake_vault", feedback.key().as_ref()], bump)]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Returns the stake to the client once `config.stake_cooldown` has passed
+/// since staking and no dispute is pending. Active feedback stays but loses
+/// its stake weight.
+pub fn unstake_feedback(mut ctx: Context<UnstakeFeedbackCtx>) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let now = Clock::get()?.unix_timestamp;
+    let cooldown = ReputationConfig::load(&accounts.config)?.stake_cooldown;
+    let unlock_at = accounts
+        .stake
+        .staked_at
+        .checked_add(cooldown)
+        .ok_or(E8004::InvalidConfig)?;
+    require!(now >= unlock_at, E8004::StakeLocked);
+    require!(!dispute_is_open(&accounts.dispute)?, E8004::StakeLocked);
+
+    let feedback = &mut accounts.feedback;
+    if feedback.is_active() {
+        if let Some(summary) = counted_summary(feedback, &mut accounts.summary)? {
+            summary.remove_stake(feedback);
+        }
+    }
+    feedback.stake = 0;
+
+    let client = accounts.client.to_account_info();
+    pay_out_stake(
+        &accounts.stake,
+        &accounts.stake_vault,
+        &accounts.token_program,
+        &client,
+        &accounts.client_token,
+        &client,
+    )?;
+
+    emit!(FeedbackUnstaked {
+        feedback: feedback.key(),
+        client: feedback.client,
+        amount: accounts.stake.amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SlashFeedbackStakeCtx<'info> {
+    #[account(mut, constraint = feedback.struck @ E8004::NotStruck)]
+    pub feedback: Account<'info, Feedback>,
+
+    #[account(
+        mut,
+        seeds=[b"rsum", feedback.agent.as_ref()],
+        bump = summary.bump
+    )]
+    pub summary: Option<Account<'info, ReputationSummary>>,
+
+    #[account(seeds=[b"rcfg"], bump = config.bump, has_one = arbiter)]
+    pub config: Account<'info, ReputationConfig>,
+
+    pub arbiter: Signer<'info>,
+
+    #[account(seeds = [b"platform"], bump = platform.bump)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        mut,
+        seeds=[b"fstake", feedback.key().as_ref()],
+        bump = stake.bump,
+        has_one = feedback,
+        close = client
+    )]
+    pub stake: Account<'info, FeedbackStake>,
+
+    /// CHECK: receives the rent of the closed escrow accounts.
+    #[account(mut, address = feedback.client)]
+    pub client: UncheckedAccount<'info>,
+
+    /// CHECK: receives slashed SOL.
+    #[account(mut, address = platform.authority)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = stake.mint.unwrap_or_default(), token::authority = platform.authority)]
+    pub treasury_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"fstake_vault", feedback.key().as_ref()], bump)]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Moves the stake behind struck feedback to the platform authority. Only
+/// the configured arbiter may slash.
+pub fn slash_feedback_stake(mut ctx: Context<SlashFeedbackStakeCtx>) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+
+    let feedback = &mut accounts.feedback;
+    if feedback.is_active() {
+        if let Some(summary) = counted_summary(feedback, &mut accounts.summary)? {
+            summary.remove_stake(feedback);
+        }
+    }
+    feedback.stake = 0;
+
+    pay_out_stake(
+        &accounts.stake,
+        &accounts.stake_vault,
+        &accounts.token_program,
+        &accounts.treasury.to_account_info(),
+        &accounts.treasury_token,
+        &accounts.client.to_account_info(),
+    )?;
+
+    emit!(FeedbackStakeSlashed {
+        feedback: feedback.key(),
+        client: feedback.client,
+        amount: accounts.stake.amount,
+        arbiter: accounts.arbiter.key()
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct FeedbackStaked {
+    pub feedback: Pubkey,
+    pub client: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeedbackUnstaked {
+    pub feedback: Pubkey,
+    pub client: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeedbackStakeSlashed {
+    pub feedback: Pubkey,
+    pub client: Pubkey,
+    pub amount: u64,
+    pub arbiter: Pubkey,
+}
@@ -0,0 +1,82 @@
+use crate::util::keccak_bytes;
+use crate::E8004;
+use anchor_lang::prelude::*;
+
+pub const MAX_TAG_LEN: usize = 64;
+pub const MAX_TAG_DESCRIPTION: usize = 256;
+
+#[account]
+pub struct TagEntry {
+    pub tag_hash: [u8; 32],
+    pub tag: String,
+    pub description: String,
+    pub registrar: Pubkey,
+    pub bump: u8,
+}
+
+/// Checks an optional human-readable tag against the 32-byte tag it names.
+pub fn verify_tag_label(tag: &[u8; 32], label: &Option<String>) -> Result<()> {
+    if let Some(label) = label {
+        require!(keccak_bytes(label.as_bytes()) == *tag, E8004::TagMismatch);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tag_hash: [u8;32])]
+pub struct RegisterTagCtx<'info> {
+    #[account(mut)]
+    pub registrar: Signer<'info>,
+
+    #[account(
+        init,
+        payer = registrar,
+        space = 8 + 32 + 4 + MAX_TAG_LEN + 4 + MAX_TAG_DESCRIPTION + 32 + 1,
+        seeds=[b"tag", tag_hash.as_ref()],
+        bump
+    )]
+    pub tag_entry: Account<'info, TagEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_tag(
+    mut ctx: Context<RegisterTagCtx>,
+    tag_hash: [u8; 32],
+    tag: String,
+    description: String,
+) -> Result<()> {
+    let tag_bump = ctx.bumps.tag_entry;
+    require!(!tag.is_empty(), E8004::Unauthorized);
+    require!(tag.len() <= MAX_TAG_LEN, E8004::Unauthorized);
+    require!(
+        description.len() <= MAX_TAG_DESCRIPTION,
+        E8004::Unauthorized
+    );
+
+    let computed = keccak_bytes(tag.as_bytes());
+    require!(computed == tag_hash, E8004::TagMismatch);
+
+    let accounts = &mut ctx.accounts;
+    let t = &mut accounts.tag_entry;
+    t.tag_hash = tag_hash;
+    t.tag = tag;
+    t.description = description;
+    t.registrar = accounts.registrar.key();
+    t.bump = tag_bump;
+
+    emit!(TagRegistered {
+        tag_hash,
+        tag: t.tag.clone(),
+        registrar: t.registrar
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TagRegistered {
+    pub tag_hash: [u8; 32],
+    pub tag: String,
+    pub registrar: Pubkey,
+}
@@ -1,10 +1,154 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
 use solana_program::{ed25519_program, keccak, sysvar::instructions::load_instruction_at_checked};
 
+/// Fixed-point scale used for time-decayed reputation weights.
+pub const DECAY_SCALE: u128 = 1_000_000_000_000;
+const LN2_SCALED: u128 = 693_147_180_560;
+
+/// Returns `2^(-elapsed / half_life)` scaled by `DECAY_SCALE`.
+///
+/// Whole half-lives are applied as shifts; the fractional remainder is
+/// `e^(-x)` with `x = ln2 * r / half_life < ln2`, evaluated as a Taylor series.
+pub fn decay_factor(elapsed: i64, half_life: i64) -> u128 {
+    if elapsed <= 0 || half_life <= 0 {
+        return DECAY_SCALE;
+    }
+    let (elapsed, half_life) = (elapsed as u128, half_life as u128);
+
+    let halvings = elapsed / half_life;
+    if halvings >= 64 {
+        return 0;
+    }
+    let whole = DECAY_SCALE >> halvings;
+
+    let x = LN2_SCALED * (elapsed % half_life) / half_life;
+    let mut term = DECAY_SCALE;
+    let mut frac = DECAY_SCALE as i128;
+    for k in 1..=12u128 {
+        term = term * x / DECAY_SCALE / k;
+        if k % 2 == 1 {
+            frac -= term as i128;
+        } else {
+            frac += term as i128;
+        }
+    }
+
+    whole * frac.max(0) as u128 / DECAY_SCALE
+}
+
 pub fn keccak_bytes(input: &[u8]) -> [u8; 32] {
     keccak::hash(input).0
 }
 
+/// Creates the program account at the PDA `target`. Anyone can transfer
+/// lamports to a PDA before it exists, which would make `create_account`
+/// fail, so a pre-funded target is topped up to rent exemption, allocated and
+/// assigned instead, as Anchor's `init` does.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let lamports = Rent::get()?.minimum_balance(space);
+    let current = target.lamports();
+    if current > 0 {
+        let top_up = lamports.saturating_sub(current);
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: target.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: target.clone(),
+                },
+                &[signer_seeds],
+            ),
+            space as u64,
+        )?;
+        return assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Assign {
+                    account_to_assign: target.clone(),
+                },
+                &[signer_seeds],
+            ),
+            &crate::ID,
+        );
+    }
+
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: target.clone(),
+            },
+            &[signer_seeds],
+        ),
+        lamports,
+        space as u64,
+        &crate::ID,
+    )
+}
+
+/// Deserializes the program account at `info`, or creates it as the PDA for
+/// `seeds` when it is still empty and returns `None`. Also returns the bump.
+/// Lamports already sent to the address do not block the creation.
+pub fn load_or_create_pda<'info, T: AccountDeserialize>(
+    info: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<(Option<T>, u8)> {
+    let (pda, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    require!(info.key() == pda, crate::E8004::Unauthorized);
+    require!(info.is_writable, crate::E8004::Unauthorized);
+
+    if info.data_is_empty() {
+        let bump_seed = [bump];
+        let mut signer_seeds = seeds.to_vec();
+        signer_seeds.push(&bump_seed);
+        create_pda_account(payer, info, system_program, space, &signer_seeds)?;
+        return Ok((None, bump));
+    }
+
+    require!(info.owner == &crate::ID, crate::E8004::Unauthorized);
+    let value = T::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    Ok((Some(value), bump))
+}
+
+/// Closes a program account that is not part of the instruction's `Accounts`,
+/// the same way Anchor's `close` constraint does.
+pub fn close_account<'info>(info: &AccountInfo<'info>, to: &AccountInfo<'info>) -> Result<()> {
+    require!(info.owner == &crate::ID, crate::E8004::Unauthorized);
+    let lamports = info.lamports();
+    **to.try_borrow_mut_lamports()? = to.lamports().checked_add(lamports).unwrap();
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&anchor_lang::system_program::ID);
+    info.realloc(0, false).map_err(Into::into)
+}
+
+pub fn store_account<T: AccountSerialize>(value: &T, info: &AccountInfo) -> Result<()> {
+    value.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
 pub struct FeedbackAuth {
     pub agent_id: u64,
     pub client: Pubkey,
@@ -87,9 +231,9 @@ pub fn verify_ed25519(
         let msg_off = u16::from_le_bytes([data[8], data[9]]) as usize;
         let pub_off = u16::from_le_bytes([data[10], data[11]]) as usize;
 
-        if sig_off.checked_add(sig_len).unwrap_or(usize::MAX) > data.len()
-            || pub_off.checked_add(32).unwrap_or(usize::MAX) > data.len()
-            || msg_off.checked_add(msg_len).unwrap_or(usize::MAX) > data.len()
+        if sig_off.saturating_add(sig_len) > data.len()
+            || pub_off.saturating_add(32) > data.len()
+            || msg_off.saturating_add(msg_len) > data.len()
         {
             continue;
         }
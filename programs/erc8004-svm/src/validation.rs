@@ -1,9 +1,26 @@
-use crate::util::keccak_bytes;
-use crate::{identity::Agent, E8004};
+use crate::attestation::{verify_attestation, Attestation, AttestationKey};
+use crate::identity::{authorize_agent_signer, Agent, AgentOperator};
+use crate::tags::verify_tag_label;
+use crate::util::{close_account, keccak_bytes};
+use crate::validator::{check_validation_policy, ValidatorEntry, METHOD_TEE};
+use crate::E8004;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount};
+use solana_program::sysvar;
 
 pub const MAX_REQUEST_URI: usize = 256;
 pub const MAX_RESPONSE_URI: usize = 256;
+pub const MAX_QUORUM_VALIDATORS: usize = 16;
+/// How long the latest response to a request without a deadline stays
+/// open to revision before the request can be closed.
+pub const CLOSE_DELAY: i64 = 7 * 86_400;
+
+/// Reason codes for `validation_decline`; other values are free for
+/// off-chain conventions.
+pub const DECLINE_UNSUPPORTED: u8 = 1;
+pub const DECLINE_CAPACITY: u8 = 2;
+pub const DECLINE_CONFLICT: u8 = 3;
 
 #[account]
 pub struct ValidationRequest {
@@ -13,6 +30,39 @@ pub struct ValidationRequest {
     pub request_hash: [u8; 32],
     pub timestamp: i64,
     pub bump: u8,
+    /// Responses recorded so far; response `n` lives at
+    /// `[b"vres", request, n]`, numbered from 1.
+    pub response_count: u32,
+    pub latest_response: Pubkey,
+    /// Last moment a response is accepted; 0 means no deadline.
+    pub deadline: i64,
+    /// Asset of the bounty; `None` escrows SOL on the request itself, a mint
+    /// escrows tokens in the `[b"vvault", request]` vault.
+    pub bounty_mint: Option<Pubkey>,
+    /// Escrowed bounty, zeroed once paid to the validator.
+    pub bounty: u64,
+    /// Value of the latest response, which is what summaries count.
+    pub latest_value: u8,
+    /// Tag of the latest response.
+    pub latest_tag: [u8; 32],
+    /// Owner or operator who opened the request; gets its rent and any
+    /// unpaid bounty back. Zero on requests opened before it was recorded.
+    pub payer: Pubkey,
+    /// Set when the validator turned the request down.
+    pub declined: bool,
+    pub decline_reason: u8,
+}
+
+impl ValidationRequest {
+    /// Where the rent and any unpaid bounty go: the payer, or `owner` for
+    /// requests without one.
+    pub fn refund_to(&self, owner: &Pubkey) -> Pubkey {
+        if self.payer == Pubkey::default() {
+            *owner
+        } else {
+            self.payer
+        }
+    }
 }
 
 #[account]
@@ -26,44 +76,185 @@ pub struct ValidationResponse {
     pub response_uri: String,
     pub response_hash: [u8; 32],
     pub bump: u8,
+    pub seq: u32,
+    /// Set once the validator has been slashed over this response.
+    pub slashed: bool,
+    /// Set while the platform challenges the response; it cannot be closed
+    /// until the challenge is dismissed or the validator slashed.
+    pub challenged: bool,
+    /// Set when the response came with a verified TEE attestation.
+    pub attested: bool,
+}
+
+/// Aggregate of validation responses, per agent at `[b"vsum", agent]` (with
+/// a default `validator`) and per pair at `[b"vsum", agent, validator]`. A
+/// request counts once, with its latest response; a quorum request counts
+/// once finalized, with its median, and only per agent.
+#[account]
+#[derive(Default)]
+pub struct ValidationSummary {
+    pub agent: Pubkey,
+    pub validator: Pubkey,
+    pub count: u64,
+    pub response_sum: u64,
+    pub last_update: i64,
+    pub bump: u8,
+}
+
+impl ValidationSummary {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+
+    /// Counts `response`, replacing `previous` when the request was already
+    /// answered.
+    pub fn record(&mut self, previous: Option<u8>, response: u8, now: i64) {
+        match previous {
+            Some(previous) => {
+                self.response_sum = self.response_sum.saturating_sub(previous as u64);
+            }
+            None => self.count = self.count.checked_add(1).unwrap(),
+        }
+        self.response_sum = self.response_sum.checked_add(response as u64).unwrap();
+        self.last_update = now;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ValidationScore {
+    pub count: u64,
+    pub average_response: u8,
+}
+
+/// A request answered by several validators, final once `threshold` of them
+/// have responded.
+#[account]
+pub struct QuorumRequest {
+    pub agent: Pubkey,
+    pub validators: Vec<Pubkey>,
+    pub threshold: u8,
+    pub request_uri: String,
+    pub request_hash: [u8; 32],
+    /// Responses in arrival order.
+    pub responses: Vec<u8>,
+    pub finalized: bool,
+    /// Aggregates over the first `threshold` responses, set on finalization.
+    pub median: u8,
+    pub mean: u8,
+    pub timestamp: i64,
+    /// Last moment a response is accepted.
+    pub deadline: i64,
+    pub finalized_at: i64,
+    pub bump: u8,
+    /// Owner or operator who opened the request; gets its rent back. Zero
+    /// on requests opened before it was recorded.
+    pub payer: Pubkey,
+}
+
+impl QuorumRequest {
+    /// Where the rent goes: the payer, or `owner` for requests without one.
+    pub fn refund_to(&self, owner: &Pubkey) -> Pubkey {
+        if self.payer == Pubkey::default() {
+            *owner
+        } else {
+            self.payer
+        }
+    }
+}
+
+/// One validator's answer to a `QuorumRequest`, at
+/// `[b"qres", request, validator]`.
+#[account]
+pub struct QuorumResponse {
+    pub request: Pubkey,
+    pub validator: Pubkey,
+    pub agent: Pubkey,
+    pub response: u8,
+    pub tag: [u8; 32],
+    pub last_update: i64,
+    pub response_uri: String,
+    pub response_hash: [u8; 32],
+    pub bump: u8,
+    /// As on `ValidationResponse`.
+    pub slashed: bool,
+    pub challenged: bool,
 }
 
 #[derive(Accounts)]
-#[instruction(request_hash: [u8;32])]
+#[instruction(validator: Pubkey, request_uri: String, request_hash: [u8; 32])]
 pub struct ValidationRequestCtx<'info> {
-    #[account(mut, constraint = agent.owner == owner.key() @ E8004::Unauthorized)]
+    #[account(mut)]
     pub agent: Account<'info, Agent>,
 
+    /// The agent owner or one of its operators; pays for the request.
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    /// Required when `owner` is an approved operator rather than the owner.
+    #[account(
+        mut,
+        seeds=[b"operator", agent.key().as_ref(), owner.key().as_ref()],
+        bump = operator.bump
+    )]
+    pub operator: Option<Account<'info, AgentOperator>>,
+
     #[account(
         init,
         payer = owner,
-        space = 8 + 32 + 32 + 4 + MAX_REQUEST_URI + 32 + 8 + 1,
+        space = 8 + 32 + 32 + 4 + MAX_REQUEST_URI + 32 + 8 + 1 + 4 + 32 + 8 + 1 + 32 + 8 + 1 + 32 + 32 + 1 + 1,
         seeds=[b"vreq", agent.key().as_ref(), &request_hash],
         bump
     )]
     pub request: Account<'info, ValidationRequest>,
 
+    /// CHECK: the agent's `[b"vpolicy", agent]` policy, which may not exist.
+    #[account(seeds=[b"vpolicy", agent.key().as_ref()], bump)]
+    pub policy: UncheckedAccount<'info>,
+
+    /// Needed when the agent's policy requires registered validators.
+    #[account(seeds=[b"validator", validator.as_ref()], bump = validator_entry.bump)]
+    pub validator_entry: Option<Account<'info, ValidatorEntry>>,
+
+    // SPL bounties only.
+    pub bounty_mint: Option<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub owner_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = bounty_mint,
+        token::authority = request,
+        seeds=[b"vvault", request.key().as_ref()],
+        bump
+    )]
+    pub bounty_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Opens a request, optionally escrowing `bounty` for the validator: in the
+/// token of `bounty_mint` when that account is passed, in SOL otherwise.
 pub fn validation_request(
     mut ctx: Context<ValidationRequestCtx>,
     validator: Pubkey,
     request_uri: String,
     request_hash: [u8; 32],
+    deadline: i64,
+    bounty: u64,
 ) -> Result<()> {
     let request_bump = ctx.bumps.request;
     let accounts = &mut ctx.accounts;
-    let owner_key = accounts.owner.key();
+    let signer_key = accounts.owner.key();
+    let now = Clock::get()?.unix_timestamp;
+    authorize_agent_signer(&accounts.agent, &signer_key, &mut accounts.operator, now)?;
 
-    require!(validator != owner_key, E8004::Unauthorized);
     require!(
-        request_uri.as_bytes().len() <= MAX_REQUEST_URI,
+        validator != signer_key && validator != accounts.agent.owner,
         E8004::Unauthorized
     );
+    require!(request_uri.len() <= MAX_REQUEST_URI, E8004::Unauthorized);
 
     let computed = keccak_bytes(
         &[
@@ -74,20 +265,78 @@ pub fn validation_request(
         .concat(),
     );
     require!(computed == request_hash, E8004::Unauthorized);
+    check_validation_policy(
+        &accounts.policy,
+        &validator,
+        accounts.validator_entry.as_deref(),
+    )?;
+
+    require!(deadline == 0 || deadline > now, E8004::RequestExpired);
+
+    // Recorded only with the vault it needs to be refunded or closed from; a
+    // mint passed for a zero bounty without a vault is ignored.
+    let bounty_mint = accounts.bounty_vault.as_ref().map(|v| v.mint);
+    if bounty > 0 {
+        match &accounts.bounty_mint {
+            None => transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: accounts.owner.to_account_info(),
+                        to: accounts.request.to_account_info(),
+                    },
+                ),
+                bounty,
+            )?,
+            Some(_) => {
+                let (Some(owner_token), Some(bounty_vault), Some(token_program)) = (
+                    &accounts.owner_token,
+                    &accounts.bounty_vault,
+                    &accounts.token_program,
+                ) else {
+                    return err!(E8004::MissingTokenAccounts);
+                };
+                token::transfer(
+                    CpiContext::new(
+                        token_program.to_account_info(),
+                        token::Transfer {
+                            from: owner_token.to_account_info(),
+                            to: bounty_vault.to_account_info(),
+                            authority: accounts.owner.to_account_info(),
+                        },
+                    ),
+                    bounty,
+                )?;
+            }
+        }
+    }
 
     let request = &mut accounts.request;
     request.agent = accounts.agent.key();
     request.validator = validator;
     request.request_uri = request_uri;
     request.request_hash = computed;
-    request.timestamp = Clock::get()?.unix_timestamp;
+    request.timestamp = now;
     request.bump = request_bump;
+    request.response_count = 0;
+    request.latest_response = Pubkey::default();
+    request.deadline = deadline;
+    request.bounty_mint = bounty_mint;
+    request.bounty = bounty;
+    request.latest_value = 0;
+    request.latest_tag = [0; 32];
+    request.payer = signer_key;
+    request.declined = false;
+    request.decline_reason = 0;
 
     emit!(ValidationRequestEv {
         validator,
         agent: request.agent,
         request: request.key(),
-        request_hash: computed
+        request_hash: computed,
+        deadline,
+        bounty_mint,
+        bounty
     });
 
     Ok(())
@@ -95,49 +344,143 @@ pub fn validation_request(
 
 #[derive(Accounts)]
 pub struct ValidationResponseCtx<'info> {
-    #[account(mut)]
+    #[account(mut, constraint = !request.declined @ E8004::RequestDeclined)]
     pub request: Account<'info, ValidationRequest>,
 
     #[account(mut)]
     pub validator: Signer<'info>,
 
     #[account(
-        init_if_needed,
+        init,
         payer = validator,
-        space = 8 + 32 + 32 + 1 + 32 + 8 + 4 + MAX_RESPONSE_URI + 32 + 1,
-        seeds=[b"vres", request.key().as_ref()],
+        space = 8 + 32 + 32 + 1 + 32 + 8 + 4 + MAX_RESPONSE_URI + 32 + 1 + 4 + 1 + 1 + 1,
+        seeds=[
+            b"vres",
+            request.key().as_ref(),
+            &(request.response_count + 1).to_le_bytes()
+        ],
         bump
     )]
     pub response_acc: Account<'info, ValidationResponse>,
 
+    #[account(
+        init_if_needed,
+        payer = validator,
+        space = ValidationSummary::SPACE,
+        seeds=[b"vsum", request.agent.as_ref()],
+        bump
+    )]
+    pub agent_summary: Account<'info, ValidationSummary>,
+
+    #[account(
+        init_if_needed,
+        payer = validator,
+        space = ValidationSummary::SPACE,
+        seeds=[b"vsum", request.agent.as_ref(), validator.key().as_ref()],
+        bump
+    )]
+    pub validator_summary: Account<'info, ValidationSummary>,
+
+    /// CHECK: the agent's `[b"vpolicy", agent]` policy, which may not exist.
+    #[account(seeds=[b"vpolicy", request.agent.as_ref()], bump)]
+    pub policy: UncheckedAccount<'info>,
+
+    /// Needed when the agent's policy requires registered validators.
+    #[account(seeds=[b"validator", validator.key().as_ref()], bump = validator_entry.bump)]
+    pub validator_entry: Option<Account<'info, ValidatorEntry>>,
+
+    // Attested responses only.
+    pub attestation_key: Option<Account<'info, AttestationKey>>,
+
+    /// CHECK: instructions sysvar, for the attestation's ed25519 instruction.
+    #[account(address = sysvar::instructions::ID)]
+    pub ix_sysvar: Option<UncheckedAccount<'info>>,
+
+    // SPL bounties only.
+    #[account(
+        mut,
+        token::mint = request.bounty_mint.unwrap_or_default(),
+        token::authority = validator
+    )]
+    pub validator_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"vvault", request.key().as_ref()], bump)]
+    pub bounty_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Records the next response to a request. Earlier responses are kept, so
+/// progressive validation leaves a trail; the request points at the latest.
+/// The first response collects the bounty. The validator must still satisfy
+/// the agent's policy, which may have changed since the request. With an
+/// `attestation`, the response is recorded as attested once the report
+/// checks out against an allowlisted `attestation_key`; only validators
+/// registered with `METHOD_TEE` can attest.
 pub fn validation_response(
     mut ctx: Context<ValidationResponseCtx>,
     response: u8,
     response_uri: String,
     response_hash: [u8; 32],
     tag: [u8; 32],
+    tag_label: Option<String>,
+    attestation: Option<Attestation>,
 ) -> Result<()> {
     let response_bump = ctx.bumps.response_acc;
+    let agent_summary_bump = ctx.bumps.agent_summary;
+    let validator_summary_bump = ctx.bumps.validator_summary;
     let accounts = &mut ctx.accounts;
     let validator_key = accounts.validator.key();
 
     require!(response <= 100, E8004::InvalidResponse);
+    verify_tag_label(&tag, &tag_label)?;
     require!(
         accounts.request.validator == validator_key,
         E8004::Unauthorized
     );
-    require!(
-        response_uri.as_bytes().len() <= MAX_RESPONSE_URI,
-        E8004::Unauthorized
-    );
+    require!(response_uri.len() <= MAX_RESPONSE_URI, E8004::Unauthorized);
+    check_validation_policy(
+        &accounts.policy,
+        &validator_key,
+        accounts.validator_entry.as_deref(),
+    )?;
 
     let request_key = accounts.request.key();
     let request_validator = accounts.request.validator;
     let request_agent = accounts.request.agent;
     let last_update = Clock::get()?.unix_timestamp;
+    let deadline = accounts.request.deadline;
+    require!(
+        deadline == 0 || last_update <= deadline,
+        E8004::RequestExpired
+    );
+    let seq = accounts.request.response_count.checked_add(1).unwrap();
+
+    let attested = match &attestation {
+        None => false,
+        Some(attestation) => {
+            let (Some(key), Some(ix_sysvar), Some(entry)) = (
+                &accounts.attestation_key,
+                &accounts.ix_sysvar,
+                &accounts.validator_entry,
+            ) else {
+                return err!(E8004::AttestationInvalid);
+            };
+            require!(entry.methods & METHOD_TEE != 0, E8004::AttestationInvalid);
+            verify_attestation(
+                attestation,
+                key,
+                &accounts.request.request_hash,
+                accounts.request.timestamp,
+                &response_hash,
+                last_update,
+                ix_sysvar,
+            )?;
+            true
+        }
+    };
 
     let response_acc = &mut accounts.response_acc;
     response_acc.request = request_key;
@@ -149,24 +492,942 @@ pub fn validation_response(
     response_acc.response_uri = response_uri;
     response_acc.response_hash = response_hash;
     response_acc.bump = response_bump;
+    response_acc.seq = seq;
+    response_acc.attested = attested;
+    response_acc.slashed = false;
+    response_acc.challenged = false;
+
+    let request = &mut accounts.request;
+    let previous = (seq > 1).then_some(request.latest_value);
+    request.response_count = seq;
+    request.latest_response = response_acc.key();
+    request.latest_value = response;
+    request.latest_tag = tag;
+
+    record_in_summaries(
+        &mut accounts.agent_summary,
+        agent_summary_bump,
+        &mut accounts.validator_summary,
+        validator_summary_bump,
+        request_agent,
+        validator_key,
+        previous,
+        response,
+        last_update,
+    );
+
+    let bounty = request.bounty;
+    if bounty > 0 {
+        match request.bounty_mint {
+            None => {
+                **request.to_account_info().try_borrow_mut_lamports()? -= bounty;
+                **accounts.validator.try_borrow_mut_lamports()? += bounty;
+            }
+            Some(_) => {
+                let (Some(validator_token), Some(bounty_vault), Some(token_program)) = (
+                    &accounts.validator_token,
+                    &accounts.bounty_vault,
+                    &accounts.token_program,
+                ) else {
+                    return err!(E8004::MissingTokenAccounts);
+                };
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        token::Transfer {
+                            from: bounty_vault.to_account_info(),
+                            to: validator_token.to_account_info(),
+                            authority: request.to_account_info(),
+                        },
+                        &[&request_seeds(request)],
+                    ),
+                    bounty,
+                )?;
+            }
+        }
+        request.bounty = 0;
+
+        emit!(ValidationBountyPaid {
+            request: request_key,
+            validator: validator_key,
+            mint: request.bounty_mint,
+            amount: bounty
+        });
+    }
 
     emit!(ValidationResponseEv {
         validator: response_acc.validator,
         agent: response_acc.agent,
         request: response_acc.request,
         response,
-        tag
+        tag,
+        tag_label,
+        seq,
+        attested
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(validators: Vec<Pubkey>, threshold: u8, request_uri: String, request_hash: [u8; 32])]
+pub struct ValidationQuorumRequestCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    /// The agent owner or one of its operators; pays for the request.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Required when `owner` is an approved operator rather than the owner.
+    #[account(
+        mut,
+        seeds=[b"operator", agent.key().as_ref(), owner.key().as_ref()],
+        bump = operator.bump
+    )]
+    pub operator: Option<Account<'info, AgentOperator>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8
+            + 32
+            + 4
+            + 32 * MAX_QUORUM_VALIDATORS
+            + 1
+            + 4
+            + MAX_REQUEST_URI
+            + 32
+            + 4
+            + MAX_QUORUM_VALIDATORS
+            + 1
+            + 1
+            + 1
+            + 8
+            + 8
+            + 8
+            + 1
+            + 32,
+        seeds=[b"vquorum", agent.key().as_ref(), &request_hash],
+        bump
+    )]
+    pub request: Account<'info, QuorumRequest>,
+
+    /// CHECK: the agent's `[b"vpolicy", agent]` policy, which may not exist.
+    #[account(seeds=[b"vpolicy", agent.key().as_ref()], bump)]
+    pub policy: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a request to `validators`, final once `threshold` of them respond
+/// by `deadline`. When the agent's policy requires registered validators,
+/// their `ValidatorEntry` accounts go in remaining accounts in `validators`
+/// order.
+pub fn validation_quorum_request(
+    mut ctx: Context<ValidationQuorumRequestCtx>,
+    validators: Vec<Pubkey>,
+    threshold: u8,
+    request_uri: String,
+    request_hash: [u8; 32],
+    deadline: i64,
+) -> Result<()> {
+    let request_bump = ctx.bumps.request;
+    let accounts = &mut ctx.accounts;
+    let signer_key = accounts.owner.key();
+    let now = Clock::get()?.unix_timestamp;
+    authorize_agent_signer(&accounts.agent, &signer_key, &mut accounts.operator, now)?;
+
+    require!(
+        !validators.is_empty() && validators.len() <= MAX_QUORUM_VALIDATORS,
+        E8004::InvalidQuorum
+    );
+    require!(
+        threshold > 0 && threshold as usize <= validators.len(),
+        E8004::InvalidQuorum
+    );
+    for (i, validator) in validators.iter().enumerate() {
+        require!(
+            *validator != signer_key && *validator != accounts.agent.owner,
+            E8004::Unauthorized
+        );
+        require!(
+            !validators[..i].contains(validator),
+            E8004::DuplicateAccount
+        );
+    }
+    require!(request_uri.len() <= MAX_REQUEST_URI, E8004::Unauthorized);
+
+    let mut preimage: Vec<u8> = validators.iter().flat_map(|v| v.to_bytes()).collect();
+    preimage.extend_from_slice(&accounts.agent.id.to_le_bytes());
+    preimage.extend_from_slice(request_uri.as_bytes());
+    let computed = keccak_bytes(&preimage);
+    require!(computed == request_hash, E8004::Unauthorized);
+
+    if !accounts.policy.data_is_empty() {
+        for (i, validator) in validators.iter().enumerate() {
+            let entry = ctx
+                .remaining_accounts
+                .get(i)
+                .map(|info| {
+                    require!(info.owner == &crate::ID, E8004::ValidatorNotRegistered);
+                    ValidatorEntry::try_deserialize(&mut &info.try_borrow_data()?[..])
+                })
+                .transpose()?;
+            check_validation_policy(&accounts.policy, validator, entry.as_ref())?;
+        }
+    }
+
+    require!(deadline > now, E8004::RequestExpired);
+
+    let request = &mut accounts.request;
+    request.agent = accounts.agent.key();
+    request.validators = validators;
+    request.threshold = threshold;
+    request.request_uri = request_uri;
+    request.request_hash = computed;
+    request.responses = Vec::new();
+    request.finalized = false;
+    request.median = 0;
+    request.mean = 0;
+    request.timestamp = now;
+    request.deadline = deadline;
+    request.finalized_at = 0;
+    request.bump = request_bump;
+    request.payer = signer_key;
+
+    emit!(ValidationQuorumRequestEv {
+        agent: request.agent,
+        request: request.key(),
+        validators: request.validators.clone(),
+        threshold,
+        request_hash: computed,
+        deadline
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ValidationQuorumResponseCtx<'info> {
+    #[account(mut, constraint = !request.finalized @ E8004::AlreadyFinalized)]
+    pub request: Account<'info, QuorumRequest>,
+
+    #[account(mut)]
+    pub validator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = validator,
+        space = 8 + 32 + 32 + 32 + 1 + 32 + 8 + 4 + MAX_RESPONSE_URI + 32 + 1 + 1 + 1,
+        seeds=[b"qres", request.key().as_ref(), validator.key().as_ref()],
+        bump
+    )]
+    pub response_acc: Account<'info, QuorumResponse>,
+
+    /// Counts the request once it is finalized.
+    #[account(
+        init_if_needed,
+        payer = validator,
+        space = ValidationSummary::SPACE,
+        seeds=[b"vsum", request.agent.as_ref()],
+        bump
+    )]
+    pub agent_summary: Account<'info, ValidationSummary>,
+
+    /// CHECK: the agent's `[b"vpolicy", agent]` policy, which may not exist.
+    #[account(seeds=[b"vpolicy", request.agent.as_ref()], bump)]
+    pub policy: UncheckedAccount<'info>,
+
+    /// Needed when the agent's policy requires registered validators.
+    #[account(seeds=[b"validator", validator.key().as_ref()], bump = validator_entry.bump)]
+    pub validator_entry: Option<Account<'info, ValidatorEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Records one named validator's response, which must still satisfy the
+/// agent's policy. The response that reaches the threshold finalizes the
+/// request with the median and mean response, and the request counts once,
+/// with its median, in the agent's validation summary. Quorum responses
+/// are not counted per validator.
+pub fn validation_quorum_response(
+    mut ctx: Context<ValidationQuorumResponseCtx>,
+    response: u8,
+    response_uri: String,
+    response_hash: [u8; 32],
+    tag: [u8; 32],
+    tag_label: Option<String>,
+) -> Result<()> {
+    let response_bump = ctx.bumps.response_acc;
+    let agent_summary_bump = ctx.bumps.agent_summary;
+    let accounts = &mut ctx.accounts;
+    let validator_key = accounts.validator.key();
+
+    require!(response <= 100, E8004::InvalidResponse);
+    verify_tag_label(&tag, &tag_label)?;
+    require!(
+        accounts.request.validators.contains(&validator_key),
+        E8004::Unauthorized
+    );
+    require!(response_uri.len() <= MAX_RESPONSE_URI, E8004::Unauthorized);
+    check_validation_policy(
+        &accounts.policy,
+        &validator_key,
+        accounts.validator_entry.as_deref(),
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let request = &mut accounts.request;
+    require!(now <= request.deadline, E8004::RequestExpired);
+
+    let response_acc = &mut accounts.response_acc;
+    response_acc.request = request.key();
+    response_acc.validator = validator_key;
+    response_acc.agent = request.agent;
+    response_acc.response = response;
+    response_acc.tag = tag;
+    response_acc.last_update = now;
+    response_acc.response_uri = response_uri;
+    response_acc.response_hash = response_hash;
+    response_acc.bump = response_bump;
+    response_acc.slashed = false;
+    response_acc.challenged = false;
+
+    request.responses.push(response);
+
+    emit!(ValidationQuorumResponseEv {
+        validator: validator_key,
+        agent: request.agent,
+        request: response_acc.request,
+        response,
+        tag,
+        tag_label
     });
 
+    if request.responses.len() == request.threshold as usize {
+        let mut sorted = request.responses.clone();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            ((sorted[n / 2 - 1] as u16 + sorted[n / 2] as u16) / 2) as u8
+        };
+        let mean = (sorted.iter().map(|r| *r as u32).sum::<u32>() / n as u32) as u8;
+
+        request.finalized = true;
+        request.median = median;
+        request.mean = mean;
+        request.finalized_at = now;
+
+        let agent_summary = &mut accounts.agent_summary;
+        if agent_summary.agent == Pubkey::default() {
+            agent_summary.agent = request.agent;
+            agent_summary.bump = agent_summary_bump;
+        }
+        agent_summary.record(None, median, now);
+
+        emit!(ValidationFinalized {
+            agent: request.agent,
+            request: response_acc.request,
+            threshold: request.threshold,
+            median,
+            mean,
+            finalized_at: now
+        });
+    }
+
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct ValidationQuorumExpireCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent,
+        constraint = request.refund_to(&agent.owner) == payer.key() @ E8004::Unauthorized,
+        close = payer,
+        constraint = !request.finalized @ E8004::AlreadyFinalized
+    )]
+    pub request: Account<'info, QuorumRequest>,
+
+    /// CHECK: receives the request's rent.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The agent owner or the payer.
+    #[account(
+        constraint = signer.key() == agent.owner || signer.key() == payer.key()
+            @ E8004::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+}
+
+/// Closes a quorum request that missed its threshold by the deadline,
+/// together with the responses it did get. The responses go in remaining
+/// accounts as `(response, validator)` pairs in `validators` order.
+pub fn validation_quorum_expire<'info>(
+    ctx: Context<'_, '_, '_, 'info, ValidationQuorumExpireCtx<'info>>,
+) -> Result<()> {
+    let request = &ctx.accounts.request;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > request.deadline, E8004::NotExpired);
+
+    close_quorum_responses(request, ctx.remaining_accounts)?;
+
+    emit!(ValidationQuorumExpired {
+        agent: request.agent,
+        request: request.key(),
+        response_count: request.responses.len() as u8,
+        deadline: request.deadline,
+        expired_at: now
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ValidationQuorumCloseCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent,
+        constraint = request.refund_to(&agent.owner) == payer.key() @ E8004::Unauthorized,
+        close = payer,
+        constraint = request.finalized @ E8004::NotFinalized
+    )]
+    pub request: Account<'info, QuorumRequest>,
+
+    /// CHECK: receives the request's rent.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The agent owner or the payer.
+    #[account(
+        constraint = signer.key() == agent.owner || signer.key() == payer.key()
+            @ E8004::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+}
+
+/// Closes a finalized quorum request once its deadline has passed, with its
+/// responses passed as in `validation_quorum_expire`. Validators that did
+/// not respond before finalization can no longer, so all responses are in.
+pub fn validation_quorum_close<'info>(
+    ctx: Context<'_, '_, '_, 'info, ValidationQuorumCloseCtx<'info>>,
+) -> Result<()> {
+    let request = &ctx.accounts.request;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > request.deadline, E8004::NotFinalized);
+
+    close_quorum_responses(request, ctx.remaining_accounts)?;
+
+    emit!(ValidationQuorumClosed {
+        agent: request.agent,
+        request: request.key(),
+        response_count: request.responses.len() as u8
+    });
+
+    Ok(())
+}
+
+/// Closes every response to `request`, given as `(response, validator)`
+/// pairs in `validators` order, returning each response's rent to its
+/// validator.
+fn close_quorum_responses<'info>(
+    request: &Account<'info, QuorumRequest>,
+    remaining: &[AccountInfo<'info>],
+) -> Result<()> {
+    require!(
+        remaining.len() == 2 * request.responses.len(),
+        E8004::ResponseMissing
+    );
+    let request_key = request.key();
+    let mut next = 0;
+    for pair in remaining.chunks(2) {
+        let (response, validator) = (&pair[0], &pair[1]);
+        let i = request.validators[next..]
+            .iter()
+            .position(|v| v == validator.key)
+            .ok_or(E8004::ResponseMissing)?;
+        next += i + 1;
+        let (pda, _) = Pubkey::find_program_address(
+            &[b"qres", request_key.as_ref(), validator.key.as_ref()],
+            &crate::ID,
+        );
+        require!(response.key() == pda, E8004::ResponseMissing);
+        let data = QuorumResponse::try_deserialize(&mut &response.try_borrow_data()?[..])?;
+        require!(!data.challenged, E8004::ResponseChallenged);
+        close_account(response, validator)?;
+    }
+    Ok(())
+}
+
+/// Counts a response in the agent's and the validator's summaries.
+#[allow(clippy::too_many_arguments)]
+fn record_in_summaries(
+    agent_summary: &mut ValidationSummary,
+    agent_summary_bump: u8,
+    validator_summary: &mut ValidationSummary,
+    validator_summary_bump: u8,
+    agent: Pubkey,
+    validator: Pubkey,
+    previous: Option<u8>,
+    response: u8,
+    now: i64,
+) {
+    if agent_summary.agent == Pubkey::default() {
+        agent_summary.agent = agent;
+        agent_summary.bump = agent_summary_bump;
+    }
+    agent_summary.record(previous, response, now);
+
+    if validator_summary.agent == Pubkey::default() {
+        validator_summary.agent = agent;
+        validator_summary.validator = validator;
+        validator_summary.bump = validator_summary_bump;
+    }
+    validator_summary.record(previous, response, now);
+}
+
+#[derive(Accounts)]
+pub struct ValidationDeclineCtx<'info> {
+    #[account(
+        mut,
+        has_one = validator,
+        constraint = !request.declined @ E8004::RequestDeclined,
+        constraint = request.response_count == 0 @ E8004::Unauthorized
+    )]
+    pub request: Account<'info, ValidationRequest>,
+
+    pub validator: Signer<'info>,
+}
+
+/// Lets the named validator turn down an unanswered request. The request
+/// takes no responses afterwards; the agent owner or the payer cancels it to
+/// reclaim the rent and any bounty, and can re-route the job under the same
+/// hash.
+pub fn validation_decline(
+    ctx: Context<ValidationDeclineCtx>,
+    reason: u8,
+    reason_uri: String,
+) -> Result<()> {
+    require!(reason_uri.len() <= MAX_RESPONSE_URI, E8004::Unauthorized);
+
+    let request = &mut ctx.accounts.request;
+    request.declined = true;
+    request.decline_reason = reason;
+
+    emit!(ValidationDeclined {
+        validator: request.validator,
+        agent: request.agent,
+        request: request.key(),
+        reason,
+        reason_uri
+    });
+
+    Ok(())
+}
+
+fn request_seeds(request: &ValidationRequest) -> [&[u8]; 4] {
+    [
+        b"vreq",
+        request.agent.as_ref(),
+        &request.request_hash,
+        std::slice::from_ref(&request.bump),
+    ]
+}
+
+/// Hands an unpaid SPL bounty back to the payer and closes the vault.
+/// SOL bounties sit on the request and return with its rent when it closes.
+fn refund_bounty<'info>(
+    request: &Account<'info, ValidationRequest>,
+    payer: &AccountInfo<'info>,
+    payer_token: &Option<Account<'info, TokenAccount>>,
+    bounty_vault: &Option<Account<'info, TokenAccount>>,
+    token_program: &Option<Program<'info, Token>>,
+) -> Result<()> {
+    if request.bounty_mint.is_none() {
+        return Ok(());
+    }
+    let (Some(bounty_vault), Some(token_program)) = (bounty_vault, token_program) else {
+        return err!(E8004::MissingTokenAccounts);
+    };
+    let seeds = request_seeds(request);
+
+    if request.bounty > 0 {
+        let Some(payer_token) = payer_token else {
+            return err!(E8004::MissingTokenAccounts);
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: bounty_vault.to_account_info(),
+                    to: payer_token.to_account_info(),
+                    authority: request.to_account_info(),
+                },
+                &[&seeds],
+            ),
+            request.bounty,
+        )?;
+    }
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: bounty_vault.to_account_info(),
+            destination: payer.clone(),
+            authority: request.to_account_info(),
+        },
+        &[&seeds],
+    ))
+}
+
+#[derive(Accounts)]
+pub struct ValidationExpireCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent,
+        constraint = request.refund_to(&agent.owner) == payer.key() @ E8004::Unauthorized,
+        close = payer,
+        constraint = request.response_count == 0 @ E8004::NotExpired
+    )]
+    pub request: Account<'info, ValidationRequest>,
+
+    /// CHECK: receives the request's rent and any refund.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The agent owner or the payer.
+    #[account(
+        constraint = signer.key() == agent.owner || signer.key() == payer.key()
+            @ E8004::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = request.bounty_mint.unwrap_or_default(),
+        token::authority = payer
+    )]
+    pub payer_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"vvault", request.key().as_ref()], bump)]
+    pub bounty_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Closes an unanswered request whose deadline has passed, returning the
+/// rent and any bounty to its payer. The agent owner or the payer signs.
+pub fn validation_expire(ctx: Context<ValidationExpireCtx>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let request = &accounts.request;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        request.deadline != 0 && now > request.deadline,
+        E8004::NotExpired
+    );
+
+    refund_bounty(
+        request,
+        &accounts.payer.to_account_info(),
+        &accounts.payer_token,
+        &accounts.bounty_vault,
+        &accounts.token_program,
+    )?;
+
+    emit!(ValidationExpired {
+        validator: request.validator,
+        agent: request.agent,
+        request: request.key(),
+        deadline: request.deadline,
+        expired_at: now
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ValidationCancelCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent,
+        constraint = request.refund_to(&agent.owner) == payer.key() @ E8004::Unauthorized,
+        close = payer,
+        constraint = request.response_count == 0 @ E8004::Unauthorized
+    )]
+    pub request: Account<'info, ValidationRequest>,
+
+    /// CHECK: receives the request's rent and any refund.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The agent owner or the payer.
+    #[account(
+        constraint = signer.key() == agent.owner || signer.key() == payer.key()
+            @ E8004::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = request.bounty_mint.unwrap_or_default(),
+        token::authority = payer
+    )]
+    pub payer_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"vvault", request.key().as_ref()], bump)]
+    pub bounty_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Withdraws a request no validator has answered yet, freeing its hash for
+/// reuse and refunding any bounty to its payer. The agent owner or the payer
+/// signs, so an operator can withdraw the requests it opened.
+pub fn validation_cancel(ctx: Context<ValidationCancelCtx>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let request = &accounts.request;
+
+    refund_bounty(
+        request,
+        &accounts.payer.to_account_info(),
+        &accounts.payer_token,
+        &accounts.bounty_vault,
+        &accounts.token_program,
+    )?;
+
+    emit!(ValidationCancelled {
+        validator: request.validator,
+        agent: request.agent,
+        request: request.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ValidationCloseCtx<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent,
+        has_one = validator,
+        constraint = request.refund_to(&agent.owner) == payer.key() @ E8004::Unauthorized,
+        close = payer,
+        constraint = request.response_count > 0 @ E8004::NotFinalized
+    )]
+    pub request: Account<'info, ValidationRequest>,
+
+    #[account(
+        mut,
+        address = request.latest_response,
+        close = validator,
+        constraint = !latest_response.challenged @ E8004::ResponseChallenged
+    )]
+    pub latest_response: Account<'info, ValidationResponse>,
+
+    /// CHECK: receives the rent of the response accounts it paid for.
+    #[account(mut)]
+    pub validator: UncheckedAccount<'info>,
+
+    /// CHECK: receives the request's rent.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The agent owner or the payer.
+    #[account(
+        constraint = signer.key() == agent.owner || signer.key() == payer.key()
+            @ E8004::Unauthorized
+    )]
+    pub signer: Signer<'info>,
+
+    // SPL bounties only; the paid-out vault is closed with the request.
+    #[account(mut, seeds=[b"vvault", request.key().as_ref()], bump)]
+    pub bounty_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Closes a finalized request together with its responses: the request's
+/// rent goes to its payer, the responses' to the validator. A request
+/// is final once answered and past its deadline or, when it has none,
+/// `CLOSE_DELAY` after its latest response. Earlier responses go in remaining
+/// accounts in `seq` order.
+pub fn validation_close<'info>(
+    ctx: Context<'_, '_, '_, 'info, ValidationCloseCtx<'info>>,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let request = &accounts.request;
+    let request_key = request.key();
+    let now = Clock::get()?.unix_timestamp;
+    let final_at = match request.deadline {
+        0 => accounts.latest_response.last_update + CLOSE_DELAY,
+        deadline => deadline,
+    };
+    require!(now > final_at, E8004::NotFinalized);
+
+    let earlier = ctx.remaining_accounts;
+    require!(
+        earlier.len() == request.response_count as usize - 1,
+        E8004::ResponseMissing
+    );
+    for (i, info) in earlier.iter().enumerate() {
+        let seq = i as u32 + 1;
+        let (pda, _) = Pubkey::find_program_address(
+            &[b"vres", request_key.as_ref(), &seq.to_le_bytes()],
+            &crate::ID,
+        );
+        require!(info.key() == pda, E8004::ResponseMissing);
+        let response = ValidationResponse::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(!response.challenged, E8004::ResponseChallenged);
+        close_account(info, &accounts.validator)?;
+    }
+
+    // Answered, so the bounty is already paid and only the vault is left.
+    refund_bounty(
+        request,
+        &accounts.payer.to_account_info(),
+        &None,
+        &accounts.bounty_vault,
+        &accounts.token_program,
+    )?;
+
+    emit!(ValidationClosed {
+        validator: request.validator,
+        agent: request.agent,
+        request: request_key,
+        response_count: request.response_count
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetValidationSummaryCtx<'info> {
+    pub agent: Account<'info, Agent>,
+}
+
+/// Returns the count and average response over `validators`, or over all
+/// validators when it is empty. Without a `tag`, the matching
+/// `[b"vsum", ...]` accounts are passed as remaining accounts; summaries not
+/// created yet count as empty. With a `tag`, the agent's `ValidationRequest`
+/// accounts to consider are passed instead, and those whose latest response
+/// carries the tag are counted. Quorum requests have no single tag and only
+/// count, untagged, in the agent-wide summary.
+pub fn get_validation_summary(
+    ctx: Context<GetValidationSummaryCtx>,
+    validators: Vec<Pubkey>,
+    tag: [u8; 32],
+) -> Result<ValidationScore> {
+    let agent_key = ctx.accounts.agent.key();
+    for (i, validator) in validators.iter().enumerate() {
+        require!(
+            !validators[..i].contains(validator),
+            E8004::DuplicateAccount
+        );
+    }
+    if tag != [0; 32] {
+        return tagged_validation_summary(ctx.remaining_accounts, agent_key, &validators, tag);
+    }
+
+    let pdas: Vec<Pubkey> = if validators.is_empty() {
+        vec![Pubkey::find_program_address(&[b"vsum", agent_key.as_ref()], &crate::ID).0]
+    } else {
+        validators
+            .iter()
+            .map(|v| {
+                Pubkey::find_program_address(&[b"vsum", agent_key.as_ref(), v.as_ref()], &crate::ID)
+                    .0
+            })
+            .collect()
+    };
+
+    let mut count = 0u64;
+    let mut response_sum = 0u64;
+    for pda in pdas {
+        let info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|a| a.key() == pda)
+            .ok_or(E8004::ValidationSummaryMissing)?;
+        if info.data_is_empty() {
+            continue;
+        }
+        require!(info.owner == &crate::ID, E8004::ValidationSummaryMissing);
+        let summary = ValidationSummary::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        count += summary.count;
+        response_sum += summary.response_sum;
+    }
+
+    Ok(ValidationScore {
+        count,
+        average_response: response_sum.checked_div(count).unwrap_or(0) as u8,
+    })
+}
+
+/// Aggregates the tagged responses among `accounts`, each a request of
+/// `agent`.
+fn tagged_validation_summary(
+    accounts: &[AccountInfo],
+    agent: Pubkey,
+    validators: &[Pubkey],
+    tag: [u8; 32],
+) -> Result<ValidationScore> {
+    let mut count = 0u64;
+    let mut response_sum = 0u64;
+    for (i, info) in accounts.iter().enumerate() {
+        require!(
+            !accounts[..i].iter().any(|a| a.key == info.key),
+            E8004::DuplicateAccount
+        );
+        require!(info.owner == &crate::ID, E8004::RequestNotFound);
+        let request = ValidationRequest::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| E8004::RequestNotFound)?;
+        require!(request.agent == agent, E8004::RequestNotFound);
+        if request.response_count == 0
+            || request.latest_tag != tag
+            || !(validators.is_empty() || validators.contains(&request.validator))
+        {
+            continue;
+        }
+        count += 1;
+        response_sum += request.latest_value as u64;
+    }
+
+    Ok(ValidationScore {
+        count,
+        average_response: response_sum.checked_div(count).unwrap_or(0) as u8,
+    })
+}
+
 #[event]
 pub struct ValidationRequestEv {
     pub validator: Pubkey,
     pub agent: Pubkey,
     pub request: Pubkey,
     pub request_hash: [u8; 32],
+    pub deadline: i64,
+    pub bounty_mint: Option<Pubkey>,
+    pub bounty: u64,
+}
+
+#[event]
+pub struct ValidationExpired {
+    pub validator: Pubkey,
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub deadline: i64,
+    pub expired_at: i64,
 }
 
 #[event]
@@ -176,4 +1437,85 @@ pub struct ValidationResponseEv {
     pub request: Pubkey,
     pub response: u8,
     pub tag: [u8; 32],
+    pub tag_label: Option<String>,
+    pub seq: u32,
+    pub attested: bool,
+}
+
+#[event]
+pub struct ValidationCancelled {
+    pub validator: Pubkey,
+    pub agent: Pubkey,
+    pub request: Pubkey,
+}
+
+#[event]
+pub struct ValidationClosed {
+    pub validator: Pubkey,
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub response_count: u32,
+}
+
+#[event]
+pub struct ValidationBountyPaid {
+    pub request: Pubkey,
+    pub validator: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ValidationQuorumRequestEv {
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub validators: Vec<Pubkey>,
+    pub threshold: u8,
+    pub request_hash: [u8; 32],
+    pub deadline: i64,
+}
+
+#[event]
+pub struct ValidationQuorumResponseEv {
+    pub validator: Pubkey,
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub response: u8,
+    pub tag: [u8; 32],
+    pub tag_label: Option<String>,
+}
+
+#[event]
+pub struct ValidationQuorumExpired {
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub response_count: u8,
+    pub deadline: i64,
+    pub expired_at: i64,
+}
+
+#[event]
+pub struct ValidationQuorumClosed {
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub response_count: u8,
+}
+
+#[event]
+pub struct ValidationFinalized {
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub threshold: u8,
+    pub median: u8,
+    pub mean: u8,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct ValidationDeclined {
+    pub validator: Pubkey,
+    pub agent: Pubkey,
+    pub request: Pubkey,
+    pub reason: u8,
+    pub reason_uri: String,
 }
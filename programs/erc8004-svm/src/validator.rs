@@ -0,0 +1,673 @@
+use crate::identity::{Agent, Platform};
+use crate::validation::{QuorumResponse, ValidationResponse};
+use crate::E8004;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount};
+
+pub const MAX_VALIDATOR_URI: usize = 256;
+
+/// Validation methods a validator declares, as bits of `methods`.
+pub const METHOD_REEXECUTION: u8 = 1 << 0;
+pub const METHOD_TEE: u8 = 1 << 1;
+pub const METHOD_ZKML: u8 = 1 << 2;
+const METHODS_ALL: u8 = METHOD_REEXECUTION | METHOD_TEE | METHOD_ZKML;
+
+/// Largest share of its stake a validator can lose to one slash, in basis
+/// points.
+pub const MAX_SLASH_BPS: u64 = 5_000;
+
+#[account]
+pub struct ValidatorConfig {
+    /// Asset validators stake in; `None` stakes SOL.
+    pub stake_mint: Option<Pubkey>,
+    pub min_stake: u64,
+    /// Time between `unbond_validator` and the stake becoming withdrawable,
+    /// during which the validator can still be slashed.
+    pub unbond_period: i64,
+    pub bump: u8,
+}
+
+/// A registered validator. SOL stakes are held as lamports on this account;
+/// SPL stakes sit in the `[b"validator_vault", validator]` token account it
+/// owns.
+#[account]
+pub struct ValidatorEntry {
+    pub validator: Pubkey,
+    pub methods: u8,
+    pub metadata_uri: String,
+    pub stake_mint: Option<Pubkey>,
+    pub stake: u64,
+    pub registered_at: i64,
+    /// When the validator started unbonding; 0 while active.
+    pub unbonding_at: i64,
+    pub bump: u8,
+}
+
+impl ValidatorEntry {
+    pub fn is_active(&self) -> bool {
+        self.unbonding_at == 0
+    }
+}
+
+/// Requirements an agent places on the validators it names.
+#[account]
+pub struct ValidationPolicy {
+    pub agent: Pubkey,
+    pub require_registered: bool,
+    pub required_methods: u8,
+    /// Stake validators need, in `stake_mint` (`None` for SOL); 0 accepts
+    /// any stake.
+    pub min_stake: u64,
+    pub stake_mint: Option<Pubkey>,
+    pub bump: u8,
+}
+
+/// Checks `validator_entry` against the agent's policy, if it has one. The
+/// entry is only needed when the policy requires registered validators.
+pub(crate) fn check_validation_policy(
+    policy: &AccountInfo,
+    validator: &Pubkey,
+    validator_entry: Option<&ValidatorEntry>,
+) -> Result<()> {
+    if policy.data_is_empty() {
+        return Ok(());
+    }
+    let policy = ValidationPolicy::try_deserialize(&mut &policy.try_borrow_data()?[..])?;
+    if !policy.require_registered {
+        return Ok(());
+    }
+
+    let Some(entry) = validator_entry else {
+        return err!(E8004::ValidatorNotRegistered);
+    };
+    require!(
+        entry.validator == *validator
+            && entry.is_active()
+            && entry.methods & policy.required_methods == policy.required_methods
+            && (policy.min_stake == 0
+                || entry.stake_mint == policy.stake_mint && entry.stake >= policy.min_stake),
+        E8004::ValidatorNotRegistered
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetValidatorConfigCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + 1 + 32 + 8 + 8 + 1,
+        seeds=[b"vcfg"],
+        bump
+    )]
+    pub config: Account<'info, ValidatorConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Changing `stake_mint` only affects validators registering afterwards.
+pub fn set_validator_config(
+    mut ctx: Context<SetValidatorConfigCtx>,
+    stake_mint: Option<Pubkey>,
+    min_stake: u64,
+    unbond_period: i64,
+) -> Result<()> {
+    let config_bump = ctx.bumps.config;
+    require!(unbond_period >= 0, E8004::InvalidConfig);
+
+    let accounts = &mut ctx.accounts;
+    let config = &mut accounts.config;
+    config.stake_mint = stake_mint;
+    config.min_stake = min_stake;
+    config.unbond_period = unbond_period;
+    config.bump = config_bump;
+
+    emit!(ValidatorConfigSet {
+        stake_mint,
+        min_stake,
+        unbond_period
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterValidatorCtx<'info> {
+    #[account(mut)]
+    pub validator: Signer<'info>,
+
+    #[account(seeds=[b"vcfg"], bump = config.bump)]
+    pub config: Account<'info, ValidatorConfig>,
+
+    #[account(
+        init,
+        payer = validator,
+        space = 8 + 32 + 1 + 4 + MAX_VALIDATOR_URI + 1 + 32 + 8 + 8 + 8 + 1,
+        seeds=[b"validator", validator.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, ValidatorEntry>,
+
+    // SPL stakes only, when `config.stake_mint` is set.
+    pub stake_mint: Option<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub validator_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = validator,
+        token::mint = stake_mint,
+        token::authority = entry,
+        seeds=[b"validator_vault", validator.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_validator(
+    mut ctx: Context<RegisterValidatorCtx>,
+    methods: u8,
+    metadata_uri: String,
+    amount: u64,
+) -> Result<()> {
+    let entry_bump = ctx.bumps.entry;
+    let accounts = &mut ctx.accounts;
+    require!(
+        methods != 0 && methods & !METHODS_ALL == 0,
+        E8004::InvalidConfig
+    );
+    require!(metadata_uri.len() <= MAX_VALIDATOR_URI, E8004::Unauthorized);
+    require!(
+        amount > 0 && amount >= accounts.config.min_stake,
+        E8004::InvalidStake
+    );
+
+    let mint = accounts.config.stake_mint;
+    match mint {
+        None => transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.validator.to_account_info(),
+                    to: accounts.entry.to_account_info(),
+                },
+            ),
+            amount,
+        )?,
+        Some(mint) => {
+            let (Some(stake_mint), Some(validator_token), Some(stake_vault), Some(token_program)) = (
+                &accounts.stake_mint,
+                &accounts.validator_token,
+                &accounts.stake_vault,
+                &accounts.token_program,
+            ) else {
+                return err!(E8004::MissingTokenAccounts);
+            };
+            require!(stake_mint.key() == mint, E8004::MintMismatch);
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token::Transfer {
+                        from: validator_token.to_account_info(),
+                        to: stake_vault.to_account_info(),
+                        authority: accounts.validator.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+
+    let entry = &mut accounts.entry;
+    entry.validator = accounts.validator.key();
+    entry.methods = methods;
+    entry.metadata_uri = metadata_uri;
+    entry.stake_mint = mint;
+    entry.stake = amount;
+    entry.registered_at = Clock::get()?.unix_timestamp;
+    entry.unbonding_at = 0;
+    entry.bump = entry_bump;
+
+    emit!(ValidatorRegistered {
+        validator: entry.validator,
+        methods,
+        stake_mint: mint,
+        stake: amount
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateValidatorCtx<'info> {
+    #[account(
+        mut,
+        seeds=[b"validator", validator.key().as_ref()],
+        bump = entry.bump,
+        has_one = validator
+    )]
+    pub entry: Account<'info, ValidatorEntry>,
+
+    pub validator: Signer<'info>,
+}
+
+pub fn update_validator(
+    ctx: Context<UpdateValidatorCtx>,
+    methods: u8,
+    metadata_uri: String,
+) -> Result<()> {
+    require!(
+        methods != 0 && methods & !METHODS_ALL == 0,
+        E8004::InvalidConfig
+    );
+    require!(metadata_uri.len() <= MAX_VALIDATOR_URI, E8004::Unauthorized);
+
+    let entry = &mut ctx.accounts.entry;
+    entry.methods = methods;
+    entry.metadata_uri = metadata_uri;
+
+    emit!(ValidatorUpdated {
+        validator: entry.validator,
+        methods
+    });
+
+    Ok(())
+}
+
+/// Starts the unbonding period. The validator no longer satisfies agent
+/// policies but stays slashable until it withdraws.
+pub fn unbond_validator(ctx: Context<UpdateValidatorCtx>) -> Result<()> {
+    let entry = &mut ctx.accounts.entry;
+    require!(entry.is_active(), E8004::StakeLocked);
+    entry.unbonding_at = Clock::get()?.unix_timestamp;
+
+    emit!(ValidatorUnbonding {
+        validator: entry.validator,
+        unbonding_at: entry.unbonding_at
+    });
+
+    Ok(())
+}
+
+/// Pays `amount` of the validator's stake out of escrow to `to` (SOL) or
+/// `to_token` (SPL).
+fn pay_out_validator_stake<'info>(
+    entry: &Account<'info, ValidatorEntry>,
+    amount: u64,
+    stake_vault: &Option<Account<'info, TokenAccount>>,
+    token_program: &Option<Program<'info, Token>>,
+    to: &AccountInfo<'info>,
+    to_token: &Option<Account<'info, TokenAccount>>,
+) -> Result<()> {
+    if entry.stake_mint.is_none() {
+        let entry_info = entry.to_account_info();
+        **entry_info.try_borrow_mut_lamports()? -= amount;
+        **to.try_borrow_mut_lamports()? += amount;
+        return Ok(());
+    }
+
+    let (Some(stake_vault), Some(to_token), Some(token_program)) =
+        (stake_vault, to_token, token_program)
+    else {
+        return err!(E8004::MissingTokenAccounts);
+    };
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: stake_vault.to_account_info(),
+                to: to_token.to_account_info(),
+                authority: entry.to_account_info(),
+            },
+            &[&[b"validator", entry.validator.as_ref(), &[entry.bump]]],
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct WithdrawValidatorStakeCtx<'info> {
+    #[account(
+        mut,
+        seeds=[b"validator", validator.key().as_ref()],
+        bump = entry.bump,
+        has_one = validator,
+        close = validator,
+        constraint = !entry.is_active() @ E8004::StakeLocked
+    )]
+    pub entry: Account<'info, ValidatorEntry>,
+
+    #[account(seeds=[b"vcfg"], bump = config.bump)]
+    pub config: Account<'info, ValidatorConfig>,
+
+    #[account(mut)]
+    pub validator: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = entry.stake_mint.unwrap_or_default(),
+        token::authority = validator
+    )]
+    pub validator_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"validator_vault", validator.key().as_ref()], bump)]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Returns what is left of the stake once the unbonding period has passed
+/// and removes the validator from the registry.
+pub fn withdraw_validator_stake(mut ctx: Context<WithdrawValidatorStakeCtx>) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let entry = &accounts.entry;
+    let now = Clock::get()?.unix_timestamp;
+    let unlock_at = entry
+        .unbonding_at
+        .checked_add(accounts.config.unbond_period)
+        .ok_or(E8004::InvalidConfig)?;
+    require!(now >= unlock_at, E8004::StakeLocked);
+
+    let validator = accounts.validator.to_account_info();
+    pay_out_validator_stake(
+        entry,
+        entry.stake,
+        &accounts.stake_vault,
+        &accounts.token_program,
+        &validator,
+        &accounts.validator_token,
+    )?;
+    if let (Some(stake_vault), Some(token_program)) =
+        (&accounts.stake_vault, &accounts.token_program)
+    {
+        token::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: stake_vault.to_account_info(),
+                destination: validator,
+                authority: entry.to_account_info(),
+            },
+            &[&[b"validator", entry.validator.as_ref(), &[entry.bump]]],
+        ))?;
+    }
+
+    emit!(ValidatorWithdrawn {
+        validator: entry.validator,
+        amount: entry.stake
+    });
+
+    Ok(())
+}
+
+/// The disputed response's slashing state, from whichever of a
+/// `ValidationResponse` or a `QuorumResponse` was passed.
+struct DisputedResponse<'a> {
+    key: Pubkey,
+    validator: Pubkey,
+    slashed: &'a mut bool,
+    challenged: &'a mut bool,
+}
+
+fn disputed_response<'a, 'info>(
+    response: &'a mut Option<Account<'info, ValidationResponse>>,
+    quorum_response: &'a mut Option<Account<'info, QuorumResponse>>,
+) -> Result<DisputedResponse<'a>> {
+    match (response, quorum_response) {
+        (Some(r), None) => {
+            let key = r.key();
+            let r: &mut ValidationResponse = r;
+            Ok(DisputedResponse {
+                key,
+                validator: r.validator,
+                slashed: &mut r.slashed,
+                challenged: &mut r.challenged,
+            })
+        }
+        (None, Some(r)) => {
+            let key = r.key();
+            let r: &mut QuorumResponse = r;
+            Ok(DisputedResponse {
+                key,
+                validator: r.validator,
+                slashed: &mut r.slashed,
+                challenged: &mut r.challenged,
+            })
+        }
+        _ => err!(E8004::ResponseMissing),
+    }
+}
+
+#[derive(Accounts)]
+pub struct ChallengeResponseCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    pub authority: Signer<'info>,
+
+    // Exactly one of the two.
+    #[account(mut)]
+    pub response: Option<Account<'info, ValidationResponse>>,
+
+    #[account(mut)]
+    pub quorum_response: Option<Account<'info, QuorumResponse>>,
+}
+
+/// Opens (`challenged`) or dismisses a challenge against a response. A
+/// challenged response cannot be closed with its request, so the evidence
+/// stays on chain until the challenge is dismissed or the validator is
+/// slashed over it.
+pub fn challenge_response(mut ctx: Context<ChallengeResponseCtx>, challenged: bool) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let response = disputed_response(&mut accounts.response, &mut accounts.quorum_response)?;
+    require!(!*response.slashed, E8004::AlreadySlashed);
+    *response.challenged = challenged;
+
+    emit!(ResponseChallenged {
+        validator: response.validator,
+        response: response.key,
+        challenged
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SlashValidatorCtx<'info> {
+    #[account(seeds = [b"platform"], bump = platform.bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    /// Receives slashed SOL.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"validator", entry.validator.as_ref()],
+        bump = entry.bump
+    )]
+    pub entry: Account<'info, ValidatorEntry>,
+
+    // The disputed response, which must be the validator's: exactly one of
+    // the two.
+    #[account(mut)]
+    pub response: Option<Account<'info, ValidationResponse>>,
+
+    #[account(mut)]
+    pub quorum_response: Option<Account<'info, QuorumResponse>>,
+
+    #[account(
+        mut,
+        token::mint = entry.stake_mint.unwrap_or_default(),
+        token::authority = authority
+    )]
+    pub treasury_token: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[b"validator_vault", entry.validator.as_ref()], bump)]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Takes `amount` of a validator's stake for the platform over a dishonest
+/// response, at most `MAX_SLASH_BPS` of the stake and once per response.
+/// Slashing settles any challenge against the response. `evidence_hash`
+/// commits to the off-chain proof and is only emitted.
+pub fn slash_validator(
+    mut ctx: Context<SlashValidatorCtx>,
+    amount: u64,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let response = disputed_response(&mut accounts.response, &mut accounts.quorum_response)?;
+    require!(
+        response.validator == accounts.entry.validator,
+        E8004::Unauthorized
+    );
+    require!(!*response.slashed, E8004::AlreadySlashed);
+    let cap = (accounts.entry.stake as u128 * MAX_SLASH_BPS as u128 / 10_000) as u64;
+    require!(amount > 0 && amount <= cap, E8004::InvalidStake);
+
+    pay_out_validator_stake(
+        &accounts.entry,
+        amount,
+        &accounts.stake_vault,
+        &accounts.token_program,
+        &accounts.authority.to_account_info(),
+        &accounts.treasury_token,
+    )?;
+
+    *response.slashed = true;
+    *response.challenged = false;
+    let entry = &mut accounts.entry;
+    entry.stake -= amount;
+
+    emit!(ValidatorSlashed {
+        validator: entry.validator,
+        response: response.key,
+        amount,
+        remaining: entry.stake,
+        evidence_hash
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetValidationPolicyCtx<'info> {
+    #[account(has_one = owner)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + 32 + 1 + 1 + 8 + 33 + 1,
+        seeds=[b"vpolicy", agent.key().as_ref()],
+        bump
+    )]
+    pub policy: Account<'info, ValidationPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_validation_policy(
+    mut ctx: Context<SetValidationPolicyCtx>,
+    require_registered: bool,
+    required_methods: u8,
+    min_stake: u64,
+    stake_mint: Option<Pubkey>,
+) -> Result<()> {
+    let policy_bump = ctx.bumps.policy;
+    require!(required_methods & !METHODS_ALL == 0, E8004::InvalidConfig);
+
+    let accounts = &mut ctx.accounts;
+    let policy = &mut accounts.policy;
+    policy.agent = accounts.agent.key();
+    policy.require_registered = require_registered;
+    policy.required_methods = required_methods;
+    policy.min_stake = min_stake;
+    policy.stake_mint = stake_mint;
+    policy.bump = policy_bump;
+
+    emit!(ValidationPolicySet {
+        agent: policy.agent,
+        require_registered,
+        required_methods,
+        min_stake,
+        stake_mint
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ValidatorConfigSet {
+    pub stake_mint: Option<Pubkey>,
+    pub min_stake: u64,
+    pub unbond_period: i64,
+}
+
+#[event]
+pub struct ValidatorRegistered {
+    pub validator: Pubkey,
+    pub methods: u8,
+    pub stake_mint: Option<Pubkey>,
+    pub stake: u64,
+}
+
+#[event]
+pub struct ValidatorUpdated {
+    pub validator: Pubkey,
+    pub methods: u8,
+}
+
+#[event]
+pub struct ValidatorUnbonding {
+    pub validator: Pubkey,
+    pub unbonding_at: i64,
+}
+
+#[event]
+pub struct ValidatorWithdrawn {
+    pub validator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ResponseChallenged {
+    pub validator: Pubkey,
+    pub response: Pubkey,
+    pub challenged: bool,
+}
+
+#[event]
+pub struct ValidatorSlashed {
+    pub validator: Pubkey,
+    pub response: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct ValidationPolicySet {
+    pub agent: Pubkey,
+    pub require_registered: bool,
+    pub required_methods: u8,
+    pub min_stake: u64,
+    pub stake_mint: Option<Pubkey>,
+}
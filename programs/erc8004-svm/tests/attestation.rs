@@ -0,0 +1,130 @@
+mod common;
+
+use anchor_lang::AnchorSerialize;
+use common::*;
+use erc8004_svm::attestation::{ATTESTATION_NITRO, ATTESTATION_SGX, MAX_ATTESTATION_AGE};
+use erc8004_svm::validation::{ValidationResponse, ValidationResponseEv};
+use erc8004_svm::validator::{METHOD_REEXECUTION, METHOD_TEE};
+use erc8004_svm::E8004;
+
+/// A TEE validator with an open request and an allowlisted SGX key.
+fn setup(w: &mut World) -> (Pubkey, Pubkey, Pubkey) {
+    let v = w.svm.funded_keypair();
+    w.register_validator(&v, METHOD_TEE, SOL).unwrap();
+    let key = Pubkey::new_unique();
+    w.register_attestation_key(&key, ATTESTATION_SGX, [0; 32])
+        .unwrap();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    (v, request, key)
+}
+
+#[test]
+fn attested_response_is_recorded() {
+    let mut w = World::new();
+    let (v, request, key) = setup(&mut w);
+    w.svm.warp(60);
+    let report = w.attestation_report(&request, ATTESTATION_SGX, [8; 32]);
+    let ev: ValidationResponseEv = w
+        .respond_attested(&v, &request, &key, report)
+        .unwrap()
+        .event();
+    assert!(ev.attested);
+    let response: ValidationResponse = w.svm.get(&w.response_key(&request, 1));
+    assert!(response.attested);
+
+    w.respond(&v, &request, 50, [0; 32]).unwrap();
+    let response: ValidationResponse = w.svm.get(&w.response_key(&request, 2));
+    assert!(!response.attested);
+}
+
+#[test]
+fn report_must_be_dated_between_request_and_response() {
+    let mut w = World::new();
+    let (v, request, key) = setup(&mut w);
+    w.svm.warp(2 * MAX_ATTESTATION_AGE);
+
+    let mut report = w.attestation_report(&request, ATTESTATION_SGX, [8; 32]);
+    report.timestamp = w.svm.now + 1;
+    assert_eq!(
+        w.respond_attested(&v, &request, &key, report.clone())
+            .unwrap_err(),
+        error(E8004::AttestationInvalid),
+        "from the future"
+    );
+    report.timestamp = w.svm.now - MAX_ATTESTATION_AGE - 1;
+    assert_eq!(
+        w.respond_attested(&v, &request, &key, report.clone())
+            .unwrap_err(),
+        error(E8004::AttestationInvalid),
+        "stale"
+    );
+    report.timestamp = w.request(&request).timestamp - 1;
+    assert_eq!(
+        w.respond_attested(&v, &request, &key, report.clone())
+            .unwrap_err(),
+        error(E8004::AttestationInvalid),
+        "older than the request"
+    );
+    report.timestamp = w.svm.now - MAX_ATTESTATION_AGE;
+    w.respond_attested(&v, &request, &key, report).unwrap();
+}
+
+#[test]
+fn report_must_match_key_and_response() {
+    let mut w = World::new();
+    let (v, request, key) = setup(&mut w);
+
+    let report = w.attestation_report(&request, ATTESTATION_NITRO, [8; 32]);
+    assert_eq!(
+        w.respond_attested(&v, &request, &key, report).unwrap_err(),
+        error(E8004::AttestationInvalid)
+    );
+    let report = w.attestation_report(&request, ATTESTATION_SGX, [9; 32]);
+    assert_eq!(
+        w.respond_attested(&v, &request, &key, report).unwrap_err(),
+        error(E8004::AttestationInvalid)
+    );
+
+    // A key pinned to another enclave measurement.
+    let pinned = Pubkey::new_unique();
+    w.register_attestation_key(&pinned, ATTESTATION_SGX, [4; 32])
+        .unwrap();
+    let report = w.attestation_report(&request, ATTESTATION_SGX, [8; 32]);
+    assert_eq!(
+        w.respond_attested(&v, &request, &pinned, report)
+            .unwrap_err(),
+        error(E8004::AttestationInvalid)
+    );
+
+    // Signed by a key other than the allowlisted one.
+    let report = w.attestation_report(&request, ATTESTATION_SGX, [8; 32]);
+    let digest = erc8004_svm::util::keccak_bytes(&report.try_to_vec().unwrap());
+    let forged = ed25519_ix(&Pubkey::new_unique(), &digest, &[6; 64]);
+    let program_ix = w.respond_ix(&v, &request, attested(report), Some(key));
+    assert_eq!(
+        w.svm.send(&[forged, program_ix]).unwrap_err(),
+        error(E8004::BadSignature)
+    );
+    assert_eq!(w.request(&request).response_count, 0);
+}
+
+#[test]
+fn only_tee_validators_attest() {
+    let mut w = World::new();
+    let key = Pubkey::new_unique();
+    w.register_attestation_key(&key, ATTESTATION_SGX, [0; 32])
+        .unwrap();
+    let (registered, unregistered) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    w.register_validator(&registered, METHOD_REEXECUTION, SOL)
+        .unwrap();
+
+    for v in [registered, unregistered] {
+        let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+        let report = w.attestation_report(&request, ATTESTATION_SGX, [8; 32]);
+        assert_eq!(
+            w.respond_attested(&v, &request, &key, report).unwrap_err(),
+            error(E8004::AttestationInvalid)
+        );
+        w.respond(&v, &request, 50, [0; 32]).unwrap();
+    }
+}
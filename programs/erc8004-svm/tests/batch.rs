@@ -0,0 +1,195 @@
+mod common;
+
+use anchor_lang::solana_program::instruction::Instruction;
+use common::*;
+use erc8004_svm::reputation::{ClientPage, FeedbackEntry, NewFeedback, MAX_FEEDBACK_BATCH};
+use erc8004_svm::E8004;
+use std::collections::HashMap;
+
+/// One batch entry: the agent rated, its score and a single tag.
+type Rating = (Pubkey, u8, [u8; 32]);
+
+fn batch_ix(
+    w: &mut World,
+    client: &Pubkey,
+    ratings: &[Rating],
+) -> (Vec<FeedbackEntry>, Vec<AccountMeta>, Vec<Instruction>) {
+    let home = w.agent;
+    let mut next: HashMap<Pubkey, u64> = HashMap::new();
+    let (mut entries, mut ed_ixs) = (vec![], vec![]);
+    let (mut remaining, mut extra) = (vec![], vec![]);
+    for &(agent, score, tag1) in ratings {
+        w.agent = agent;
+        let idx = pda(&[b"idx", agent.as_ref(), client.as_ref()]);
+        let index = next.entry(agent).or_insert_with(|| {
+            if w.svm.exists(&idx) {
+                w.svm
+                    .get::<erc8004_svm::reputation::ClientIndex>(&idx)
+                    .last_index
+            } else {
+                0
+            }
+        });
+        *index += 1;
+        let (auth, signature, ed_ix) = w.feedback_auth(client);
+        entries.push(FeedbackEntry {
+            score,
+            tag1,
+            tag2: [0; 32],
+            file_uri: "ipfs://batch".into(),
+            file_hash: [2; 32],
+            auth_struct_bytes: auth,
+            signature: signature.to_vec(),
+            expected_index: *index,
+        });
+        ed_ixs.push(ed_ix);
+        remaining.extend([
+            AccountMeta::new_readonly(agent, false),
+            AccountMeta::new(idx, false),
+            AccountMeta::new(w.feedback_key(client, *index), false),
+            AccountMeta::new(w.summary_key(), false),
+        ]);
+        extra.extend(w.tag_metas(&[tag1]));
+        let clients = if w.svm.exists(&w.summary_key()) {
+            w.summary().client_count
+        } else {
+            0
+        };
+        let page = (clients / erc8004_svm::reputation::CLIENTS_PER_PAGE as u64) as u32;
+        extra.push(AccountMeta::new(
+            pda(&[b"clients", agent.as_ref(), &page.to_le_bytes()]),
+            false,
+        ));
+    }
+    w.agent = home;
+    remaining.extend(extra);
+    (entries, remaining, ed_ixs)
+}
+
+fn batch(
+    w: &mut World,
+    client: &Pubkey,
+    ratings: &[Rating],
+) -> std::result::Result<TxResult, ProgramError> {
+    let (entries, remaining, ixs) = batch_ix(w, client, ratings);
+    send_batch(w, client, entries, &remaining, ixs)
+}
+
+/// Sends `give_feedback_batch` after the entries' ed25519 instructions.
+fn send_batch(
+    w: &mut World,
+    client: &Pubkey,
+    entries: Vec<FeedbackEntry>,
+    remaining: &[AccountMeta],
+    mut ixs: Vec<Instruction>,
+) -> std::result::Result<TxResult, ProgramError> {
+    ixs.push(ix(
+        erc8004_svm::accounts::GiveFeedbackBatchCtx {
+            client: *client,
+            config: pda(&[b"rcfg"]),
+            ix_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        erc8004_svm::instruction::GiveFeedbackBatch { entries },
+        remaining,
+    ));
+    w.svm.send(&ixs)
+}
+
+#[test]
+fn batch_rates_several_agents() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let owner = w.svm.funded_keypair();
+    let (first, second) = (w.agent, w.register_agent(&owner));
+    let speed = tag("speed");
+
+    let result = batch(&mut w, &a, &[(first, 90, speed), (second, 40, [0; 32])]).unwrap();
+    let events = result.events::<NewFeedback>();
+    assert_eq!(events.len(), 2);
+    assert_eq!((events[0].agent, events[0].score), (first, 90));
+    assert_eq!((events[1].agent, events[1].score), (second, 40));
+
+    let f = w.feedback(&a, 1);
+    assert_eq!((f.score, f.tag1, f.counted), (90, speed, true));
+    assert_eq!(w.tag_summary(&speed).score_sum, 90);
+    let s = w.summary();
+    assert_eq!((s.feedback_count, s.score_sum, s.client_count), (1, 90, 1));
+    let page: ClientPage = w
+        .svm
+        .get(&pda(&[b"clients", first.as_ref(), &0u32.to_le_bytes()]));
+    assert_eq!(page.clients, vec![a]);
+
+    w.agent = second;
+    assert_eq!(w.feedback(&a, 1).score, 40);
+    let s = w.summary();
+    assert_eq!((s.feedback_count, s.score_sum, s.client_count), (1, 40, 1));
+    w.agent = first;
+
+    // The same agent twice takes consecutive indices; the client is only
+    // listed once.
+    batch(&mut w, &a, &[(first, 10, [0; 32]), (first, 20, [0; 32])]).unwrap();
+    assert_eq!((w.feedback(&a, 2).score, w.feedback(&a, 3).score), (10, 20));
+    let s = w.summary();
+    assert_eq!((s.feedback_count, s.client_count), (3, 1));
+    w.give(&a, Give::score(50)).unwrap();
+    assert!(w.svm.exists(&w.feedback_key(&a, 4)));
+}
+
+#[test]
+fn batch_is_bounded_and_checked() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let agent = w.agent;
+
+    assert_eq!(
+        batch(&mut w, &a, &[]).unwrap_err(),
+        error(E8004::InvalidBatch)
+    );
+    let many = vec![(agent, 50, [0; 32]); MAX_FEEDBACK_BATCH + 1];
+    assert_eq!(
+        batch(&mut w, &a, &many).unwrap_err(),
+        error(E8004::InvalidBatch)
+    );
+
+    let (entries, remaining, ixs) = batch_ix(&mut w, &a, &[(agent, 50, [0; 32])]);
+    assert_eq!(
+        send_batch(&mut w, &a, entries, &remaining[..3], ixs).unwrap_err(),
+        error(E8004::InvalidBatch)
+    );
+
+    assert_eq!(
+        batch(&mut w, &a, &[(agent, 101, [0; 32])]).unwrap_err(),
+        error(E8004::InvalidScore)
+    );
+    let owner = w.owner;
+    assert_eq!(
+        batch(&mut w, &owner, &[(agent, 50, [0; 32])]).unwrap_err(),
+        error(E8004::SelfFeedback)
+    );
+
+    // An entry pointing at an account the program does not own.
+    let (entries, mut remaining, ixs) = batch_ix(&mut w, &a, &[(agent, 50, [0; 32])]);
+    remaining[0] = AccountMeta::new_readonly(w.svm.funded_keypair(), false);
+    assert_eq!(
+        send_batch(&mut w, &a, entries, &remaining, ixs).unwrap_err(),
+        error(E8004::AgentNotFound)
+    );
+}
+
+#[test]
+fn failed_entry_rolls_back_the_batch() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let agent = w.agent;
+
+    let (mut entries, remaining, ixs) =
+        batch_ix(&mut w, &a, &[(agent, 50, [0; 32]), (agent, 60, [0; 32])]);
+    entries[1].expected_index = 5;
+    assert_eq!(
+        send_batch(&mut w, &a, entries, &remaining, ixs).unwrap_err(),
+        error(E8004::IndexLimit)
+    );
+    assert!(!w.svm.exists(&w.feedback_key(&a, 1)));
+    assert!(!w.svm.exists(&w.summary_key()));
+}
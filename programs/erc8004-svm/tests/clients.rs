@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use erc8004_svm::reputation::{ClientPage, CLIENTS_PER_PAGE};
+
+fn page_key(w: &World, page: u32) -> Pubkey {
+    pda(&[b"clients", w.agent.as_ref(), &page.to_le_bytes()])
+}
+
+fn clients(w: &mut World, page: u32) -> Vec<Pubkey> {
+    let (agent, client_page) = (w.agent, page_key(w, page));
+    w.svm
+        .call(
+            erc8004_svm::accounts::GetClientsCtx { agent, client_page },
+            erc8004_svm::instruction::GetClients { page },
+            &[],
+        )
+        .unwrap()
+        .returned()
+}
+
+#[test]
+fn clients_are_listed_once_across_pages() {
+    let mut w = World::new();
+    let first: Vec<Pubkey> = (0..CLIENTS_PER_PAGE)
+        .map(|_| w.svm.funded_keypair())
+        .collect();
+    for client in &first {
+        w.give(client, Give::score(50)).unwrap();
+    }
+    w.give(&first[0], Give::score(60)).unwrap();
+    assert_eq!(clients(&mut w, 0), first);
+    assert!(!w.svm.exists(&page_key(&w, 1)));
+
+    let late = w.svm.funded_keypair();
+    w.give(&late, Give::score(50)).unwrap();
+    assert_eq!(clients(&mut w, 1), vec![late]);
+    let page: ClientPage = w.svm.get(&page_key(&w, 1));
+    assert_eq!((page.agent, page.page), (w.agent, 1));
+    assert_eq!(w.summary().client_count, CLIENTS_PER_PAGE as u64 + 1);
+}
+
+#[test]
+fn prefunded_client_page_is_still_created() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let key = page_key(&w, 0);
+    let rent = Rent::default().minimum_balance(ClientPage::SPACE);
+
+    // Enough to block a plain create_account, not enough for rent.
+    w.svm.airdrop(&key, 1);
+    w.give(&a, Give::score(50)).unwrap();
+
+    let account = &w.svm.accounts[&key];
+    assert_eq!(account.owner, erc8004_svm::ID);
+    assert_eq!(account.lamports, rent);
+    assert_eq!(clients(&mut w, 0), vec![a]);
+}
+
+#[test]
+fn overfunded_client_page_keeps_its_lamports() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let key = page_key(&w, 0);
+    w.svm.airdrop(&key, SOL);
+
+    let before = w.svm.lamports(&a);
+    w.give(&a, Give::score(50)).unwrap();
+    assert_eq!(w.svm.lamports(&key), SOL);
+    assert_eq!(w.svm.accounts[&key].owner, erc8004_svm::ID);
+    assert_eq!(clients(&mut w, 0), vec![a]);
+
+    // The client paid for the other new accounts, not the page.
+    let idx = pda(&[b"idx", w.agent.as_ref(), a.as_ref()]);
+    let created = [idx, w.feedback_key(&a, 1), w.summary_key()];
+    let paid: u64 = created.iter().map(|k| w.svm.lamports(k)).sum();
+    assert_eq!(before - w.svm.lamports(&a), paid);
+}
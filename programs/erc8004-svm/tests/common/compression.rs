@@ -0,0 +1,227 @@
+//! The spl-account-compression instructions the program invokes
+//! (`init_empty_merkle_tree`, `append` and `replace_leaf`), ported over
+//! spl-concurrent-merkle-tree. The published program crate pins anchor-lang
+//! 0.28 and cannot be linked next to this one; the handlers below follow its
+//! 0.2.0 sources, minus canopies and the noop change log CPI.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::keccak;
+use erc8004_svm::compressed::SPL_NOOP_ID;
+use spl_concurrent_merkle_tree::concurrent_merkle_tree::ConcurrentMerkleTree;
+use spl_concurrent_merkle_tree::node::{empty_node, Node};
+use std::mem::size_of;
+
+const HEADER_SIZE: usize = 2 + 54;
+const ACCOUNT_TYPE_TREE: u8 = 1;
+
+// AccountCompressionError, numbered from anchor's 6000.
+const CONCURRENT_MERKLE_TREE_ERROR: u32 = 6001;
+const ZERO_COPY_ERROR: u32 = 6002;
+const CONSTANTS_ERROR: u32 = 6003;
+const INCORRECT_AUTHORITY: u32 = 6005;
+const INCORRECT_ACCOUNT_OWNER: u32 = 6006;
+const INCORRECT_ACCOUNT_TYPE: u32 = 6007;
+const LEAF_INDEX_OUT_OF_BOUNDS: u32 = 6008;
+
+/// `ConcurrentMerkleTreeHeader` with its `V1` variant inlined.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct Header {
+    account_type: u8,
+    version: u8,
+    max_buffer_size: u32,
+    max_depth: u32,
+    authority: Pubkey,
+    creation_slot: u64,
+    padding: [u8; 6],
+}
+
+/// Tree sizes the harness supports, out of those the program accepts.
+macro_rules! with_tree {
+    ($header:expr, $bytes:expr, |$tree:ident| $body:expr) => {
+        match ($header.max_depth, $header.max_buffer_size) {
+            (3, 8) => with_tree!(@load 3, 8, $bytes, $tree, $body),
+            (5, 8) => with_tree!(@load 5, 8, $bytes, $tree, $body),
+            (14, 64) => with_tree!(@load 14, 64, $bytes, $tree, $body),
+            _ => Err(ProgramError::Custom(CONSTANTS_ERROR)),
+        }
+    };
+    (@load $depth:literal, $buffer:literal, $bytes:expr, $tree:ident, $body:expr) => {{
+        let size = size_of::<ConcurrentMerkleTree<$depth, $buffer>>();
+        assert!($bytes.len() <= size, "canopies are not emulated");
+        match bytemuck::try_from_bytes_mut::<ConcurrentMerkleTree<$depth, $buffer>>($bytes) {
+            Ok($tree) => $body.map_err(|_| ProgramError::Custom(CONCURRENT_MERKLE_TREE_ERROR)),
+            Err(_) => Err(ProgramError::Custom(ZERO_COPY_ERROR)),
+        }
+    }};
+}
+
+fn discriminator(name: &str) -> [u8; 8] {
+    hash(format!("global:{name}").as_bytes()).to_bytes()[..8]
+        .try_into()
+        .unwrap()
+}
+
+fn array(data: &[u8], at: usize) -> std::result::Result<[u8; 32], ProgramError> {
+    data.get(at..at + 32)
+        .map(|b| b.try_into().unwrap())
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+/// Runs one spl-account-compression instruction; `accounts` are in the
+/// instruction's order, signer flags already checked.
+pub fn process(accounts: &[AccountInfo], data: &[u8], slot: u64) -> ProgramResult {
+    let [merkle_tree, authority, noop, proof @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *noop.key != SPL_NOOP_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if *merkle_tree.owner != erc8004_svm::compressed::SPL_ACCOUNT_COMPRESSION_ID {
+        return Err(ProgramError::Custom(INCORRECT_ACCOUNT_OWNER));
+    }
+    if data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (tag, args) = data.split_at(8);
+
+    let mut bytes = merkle_tree.try_borrow_mut_data()?;
+    if bytes.len() < HEADER_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let (header_bytes, tree_bytes) = bytes.split_at_mut(HEADER_SIZE);
+    let mut header = Header::try_from_slice(header_bytes)?;
+    if header.version != 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if tag == discriminator("init_empty_merkle_tree") {
+        if !proof.is_empty() || header_bytes[..8] != [0; 8] {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if args.len() != 8 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        header.account_type = ACCOUNT_TYPE_TREE;
+        header.max_depth = u32::from_le_bytes(args[..4].try_into().unwrap());
+        header.max_buffer_size = u32::from_le_bytes(args[4..].try_into().unwrap());
+        header.authority = *authority.key;
+        header.creation_slot = slot;
+        header_bytes.copy_from_slice(&header.try_to_vec()?);
+        return with_tree!(header, tree_bytes, |tree| tree.initialize()).map(drop);
+    }
+
+    if header.account_type != ACCOUNT_TYPE_TREE {
+        return Err(ProgramError::Custom(INCORRECT_ACCOUNT_TYPE));
+    }
+    if header.authority != *authority.key {
+        return Err(ProgramError::Custom(INCORRECT_AUTHORITY));
+    }
+    if tag == discriminator("append") {
+        let leaf = array(args, 0)?;
+        with_tree!(header, tree_bytes, |tree| tree.append(leaf)).map(drop)
+    } else if tag == discriminator("replace_leaf") {
+        let (root, previous_leaf, new_leaf) = (array(args, 0)?, array(args, 32)?, array(args, 64)?);
+        let index = args
+            .get(96..100)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        if index >= 1 << header.max_depth {
+            return Err(ProgramError::Custom(LEAF_INDEX_OUT_OF_BOUNDS));
+        }
+        let proof: Vec<Node> = proof.iter().map(|a| a.key.to_bytes()).collect();
+        with_tree!(header, tree_bytes, |tree| tree.set_leaf(
+            root,
+            previous_leaf,
+            new_leaf,
+            &proof,
+            index
+        ))
+        .map(drop)
+    } else {
+        Err(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Bytes to allocate for a tree without canopy.
+pub fn tree_space(max_depth: u32, max_buffer_size: u32) -> usize {
+    HEADER_SIZE
+        + match (max_depth, max_buffer_size) {
+            (3, 8) => size_of::<ConcurrentMerkleTree<3, 8>>(),
+            (5, 8) => size_of::<ConcurrentMerkleTree<5, 8>>(),
+            (14, 64) => size_of::<ConcurrentMerkleTree<14, 64>>(),
+            _ => panic!("unsupported tree size"),
+        }
+}
+
+/// The current root of the tree stored in `data`.
+pub fn tree_root(data: &[u8]) -> Node {
+    let mut data = data.to_vec();
+    let (header_bytes, tree_bytes) = data.split_at_mut(HEADER_SIZE);
+    let header = Header::try_from_slice(header_bytes).unwrap();
+    let root: std::result::Result<Node, ProgramError> =
+        with_tree!(header, tree_bytes, |tree| Ok::<_, ()>(tree.get_root()));
+    root.unwrap()
+}
+
+/// An off-chain copy of a tree's leaves, for roots and proofs.
+pub struct MerkleTree {
+    depth: u32,
+    leaves: Vec<Node>,
+}
+
+impl MerkleTree {
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth,
+            leaves: Vec::new(),
+        }
+    }
+
+    pub fn append(&mut self, leaf: Node) {
+        self.leaves.push(leaf);
+    }
+
+    pub fn replace(&mut self, index: u32, leaf: Node) {
+        self.leaves[index as usize] = leaf;
+    }
+
+    /// Nodes of `level`, without the empty ones on the right.
+    fn level(&self, level: u32) -> Vec<Node> {
+        let mut nodes = self.leaves.clone();
+        for l in 0..level {
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| {
+                    let right = pair.get(1).copied().unwrap_or_else(|| empty_node(l));
+                    keccak::hashv(&[&pair[0], &right]).0
+                })
+                .collect();
+        }
+        nodes
+    }
+
+    pub fn root(&self) -> Node {
+        self.level(self.depth)
+            .first()
+            .copied()
+            .unwrap_or_else(|| empty_node(self.depth))
+    }
+
+    /// Sibling nodes from the leaf up, as the proof accounts expect them.
+    pub fn proof(&self, index: u32) -> Vec<Node> {
+        (0..self.depth)
+            .map(|l| {
+                let sibling = ((index >> l) ^ 1) as usize;
+                self.level(l)
+                    .get(sibling)
+                    .copied()
+                    .unwrap_or_else(|| empty_node(l))
+            })
+            .collect()
+    }
+}
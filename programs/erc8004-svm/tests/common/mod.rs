@@ -0,0 +1,1982 @@
+//! A minimal in-process runtime for driving the program through its
+//! entrypoint: accounts are serialized the way the BPF loader does, system
+//! program CPIs are emulated, token CPIs run the SPL Token processor,
+//! account compression CPIs run the port in `compression`, and clock, rent,
+//! events and return data go through `SyscallStubs`. Ed25519 instructions are
+//! not verified; the program only looks for them in the instructions sysvar.
+#![allow(dead_code)]
+
+pub mod compression;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::{self, ProgramResult, MAX_PERMITTED_DATA_INCREASE};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use anchor_lang::solana_program::system_instruction::SystemInstruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction,
+};
+use anchor_lang::solana_program::{
+    ed25519_program, program_utils::limited_deserialize, system_program, sysvar,
+};
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::token;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Once;
+
+pub use anchor_lang::prelude::{AccountMeta, ProgramError, Pubkey, Rent};
+
+pub const SOL: u64 = 1_000_000_000;
+const NON_DUP_MARKER: u8 = u8::MAX;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+#[derive(Default)]
+struct SyscallState {
+    clock: Clock,
+    logs: Vec<Vec<u8>>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    cpis: Vec<Instruction>,
+}
+
+thread_local! {
+    static STATE: RefCell<SyscallState> = RefCell::new(SyscallState::default());
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        STATE.with(|s| s.borrow_mut().logs.push(fields.concat()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = STATE.with(|s| s.borrow().clock.clone());
+        unsafe { *(var_addr as *mut Clock) = clock };
+        entrypoint::SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        entrypoint::SUCCESS
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        STATE.with(|s| s.borrow_mut().return_data = Some((erc8004_svm::ID, data.to_vec())));
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        STATE.with(|s| s.borrow().return_data.clone())
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        ix: &Instruction,
+        infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let pdas: Vec<Pubkey> = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &erc8004_svm::ID).unwrap())
+            .collect();
+        let mut accounts = Vec::with_capacity(ix.accounts.len());
+        for meta in &ix.accounts {
+            let info = infos
+                .iter()
+                .find(|i| *i.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            if meta.is_signer && !info.is_signer && !pdas.contains(info.key) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            accounts.push(info);
+        }
+        STATE.with(|s| s.borrow_mut().cpis.push(ix.clone()));
+
+        // The callee sees the privileges of the instruction, which were
+        // checked above.
+        let callee_infos = || -> Vec<AccountInfo> {
+            ix.accounts
+                .iter()
+                .zip(&accounts)
+                .map(|(meta, &info)| {
+                    let mut info = info.clone();
+                    info.is_signer = meta.is_signer;
+                    info.is_writable = meta.is_writable;
+                    info
+                })
+                .collect()
+        };
+        match ix.program_id {
+            system_program::ID => {}
+            token::ID => {
+                return token::spl_token::processor::Processor::process(
+                    &ix.program_id,
+                    &callee_infos(),
+                    &ix.data,
+                )
+            }
+            erc8004_svm::compressed::SPL_ACCOUNT_COMPRESSION_ID => {
+                let slot = STATE.with(|s| s.borrow().clock.slot);
+                return compression::process(&callee_infos(), &ix.data, slot);
+            }
+            erc8004_svm::compressed::SPL_NOOP_ID => return Ok(()),
+            _ => return Err(ProgramError::IncorrectProgramId),
+        }
+
+        let signed = |info: &AccountInfo| info.is_signer || pdas.contains(info.key);
+        match limited_deserialize::<SystemInstruction>(&ix.data, 1024)
+            .map_err(|_| ProgramError::InvalidInstructionData)?
+        {
+            SystemInstruction::CreateAccount {
+                lamports,
+                space,
+                owner,
+            } => {
+                let (from, to) = (accounts[0], accounts[1]);
+                if !signed(from) || !signed(to) {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if to.lamports() > 0 || !to.data_is_empty() {
+                    // SystemError::AccountAlreadyInUse
+                    return Err(ProgramError::Custom(0));
+                }
+                debit(from, lamports)?;
+                **to.try_borrow_mut_lamports()? += lamports;
+                to.realloc(space as usize, true)?;
+                to.assign(&owner);
+            }
+            SystemInstruction::Transfer { lamports } => {
+                let (from, to) = (accounts[0], accounts[1]);
+                if !signed(from) {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if !from.data_is_empty() || *from.owner != system_program::ID {
+                    return Err(ProgramError::InvalidArgument);
+                }
+                debit(from, lamports)?;
+                **to.try_borrow_mut_lamports()? += lamports;
+            }
+            SystemInstruction::Allocate { space } => {
+                let target = accounts[0];
+                if !signed(target) {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if !target.data_is_empty() || *target.owner != system_program::ID {
+                    return Err(ProgramError::Custom(0));
+                }
+                target.realloc(space as usize, true)?;
+            }
+            SystemInstruction::Assign { owner } => {
+                let target = accounts[0];
+                if !signed(target) {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if *target.owner != system_program::ID {
+                    return Err(ProgramError::IllegalOwner);
+                }
+                target.assign(&owner);
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        }
+        Ok(())
+    }
+}
+
+fn debit(info: &AccountInfo, lamports: u64) -> ProgramResult {
+    let mut balance = info.try_borrow_mut_lamports()?;
+    // SystemError::ResultWithNegativeLamports
+    **balance = balance
+        .checked_sub(lamports)
+        .ok_or(ProgramError::Custom(1))?;
+    Ok(())
+}
+
+/// Outcome of a successful transaction.
+#[derive(Debug, Default)]
+pub struct TxResult {
+    pub logs: Vec<Vec<u8>>,
+    pub return_data: Option<Vec<u8>>,
+    /// Every CPI the program made, in order.
+    pub cpis: Vec<Instruction>,
+}
+
+impl TxResult {
+    pub fn events<E: anchor_lang::Event + AnchorDeserialize + Discriminator>(&self) -> Vec<E> {
+        self.logs
+            .iter()
+            .filter(|l| l.starts_with(&E::DISCRIMINATOR))
+            .map(|l| E::deserialize(&mut &l[8..]).unwrap())
+            .collect()
+    }
+
+    pub fn event<E: anchor_lang::Event + AnchorDeserialize + Discriminator>(&self) -> E {
+        let mut events = self.events::<E>();
+        assert_eq!(events.len(), 1, "expected exactly one event");
+        events.pop().unwrap()
+    }
+
+    pub fn returned<T: AnchorDeserialize>(&self) -> T {
+        T::deserialize(&mut self.return_data.as_deref().expect("no return data")).unwrap()
+    }
+}
+
+pub struct Svm {
+    pub accounts: HashMap<Pubkey, AccountState>,
+    pub now: i64,
+    pub slot: u64,
+    /// Token accounts made by `create_token_account`, by owner and mint.
+    pub token_accounts: HashMap<(Pubkey, Pubkey), Pubkey>,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut svm = Svm {
+            accounts: HashMap::new(),
+            now: 1_700_000_000,
+            slot: 1,
+            token_accounts: HashMap::new(),
+        };
+        for program in [
+            erc8004_svm::ID,
+            system_program::ID,
+            ed25519_program::ID,
+            token::ID,
+            erc8004_svm::compressed::SPL_ACCOUNT_COMPRESSION_ID,
+            erc8004_svm::compressed::SPL_NOOP_ID,
+        ] {
+            svm.accounts.insert(
+                program,
+                AccountState {
+                    lamports: 1,
+                    owner: Pubkey::new_unique(),
+                    executable: true,
+                    ..Default::default()
+                },
+            );
+        }
+        svm
+    }
+
+    pub fn warp(&mut self, seconds: i64) {
+        self.now += seconds;
+        self.slot += (seconds.max(0) as u64) * 2 + 1;
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_insert_with(|| AccountState {
+            owner: system_program::ID,
+            ..Default::default()
+        });
+        account.lamports += lamports;
+    }
+
+    pub fn funded_keypair(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.airdrop(&key, 100 * SOL);
+        key
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |a| a.lamports)
+    }
+
+    /// Creates an SPL mint without a mint authority; supply comes from
+    /// `create_token_account`.
+    pub fn create_mint(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let mint = token::spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        self.put_token_state(&key, mint);
+        key
+    }
+
+    /// Creates a token account of `mint` owned by `owner` holding `amount`.
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        use token::spl_token::state::{Account, AccountState, Mint};
+        let key = Pubkey::new_unique();
+        self.put_token_state(
+            &key,
+            Account {
+                mint: *mint,
+                owner: *owner,
+                amount,
+                state: AccountState::Initialized,
+                ..Default::default()
+            },
+        );
+        let mut state = Mint::unpack(&self.accounts[mint].data).unwrap();
+        state.supply += amount;
+        self.put_token_state(mint, state);
+        self.token_accounts.insert((*owner, *mint), key);
+        key
+    }
+
+    /// The last token account of `mint` created for `owner`.
+    pub fn token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Option<Pubkey> {
+        self.token_accounts.get(&(*owner, *mint)).copied()
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let account = &self.accounts[key];
+        assert_eq!(account.owner, token::ID, "not a token account");
+        token::spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    fn put_token_state<T: Pack>(&mut self, key: &Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).unwrap();
+        self.accounts.insert(
+            *key,
+            AccountState {
+                lamports: Rent::default().minimum_balance(T::LEN),
+                data,
+                owner: token::ID,
+                executable: false,
+            },
+        );
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts.contains_key(key)
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self
+            .accounts
+            .get(key)
+            .unwrap_or_else(|| panic!("account {key} does not exist"));
+        assert_eq!(
+            account.owner,
+            erc8004_svm::ID,
+            "account {key} not owned by the program"
+        );
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    /// Writes `value` as a program account, e.g. to fake state older versions
+    /// of the program left behind.
+    pub fn put<T: AccountSerialize>(&mut self, key: &Pubkey, value: &T, space: usize) {
+        let mut data = Vec::with_capacity(space);
+        value.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= space);
+        data.resize(space, 0);
+        self.accounts.insert(
+            *key,
+            AccountState {
+                lamports: Rent::default().minimum_balance(space),
+                data,
+                owner: erc8004_svm::ID,
+                executable: false,
+            },
+        );
+    }
+
+    /// Runs a single program instruction.
+    pub fn call(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+        remaining: &[AccountMeta],
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.send(&[ix(accounts, data, remaining)])
+    }
+
+    /// Runs `ixs` as one transaction: state changes only persist when every
+    /// instruction succeeds.
+    pub fn send(&mut self, ixs: &[Instruction]) -> std::result::Result<TxResult, ProgramError> {
+        let snapshot = self.accounts.clone();
+        let result = self.execute(ixs);
+        if result.is_err() {
+            self.accounts = snapshot;
+        }
+        result
+    }
+
+    fn execute(&mut self, ixs: &[Instruction]) -> std::result::Result<TxResult, ProgramError> {
+        let borrowed: Vec<BorrowedInstruction> = ixs
+            .iter()
+            .map(|ix| BorrowedInstruction {
+                program_id: &ix.program_id,
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|m| BorrowedAccountMeta {
+                        pubkey: &m.pubkey,
+                        is_signer: m.is_signer,
+                        is_writable: m.is_writable,
+                    })
+                    .collect(),
+                data: &ix.data,
+            })
+            .collect();
+        let ix_sysvar = construct_instructions_data(&borrowed);
+
+        let mut result = TxResult::default();
+        for (i, ix) in ixs.iter().enumerate() {
+            if ix.program_id == ed25519_program::ID {
+                continue;
+            }
+            assert_eq!(ix.program_id, erc8004_svm::ID, "unsupported program");
+
+            let mut sysvar_data = ix_sysvar.clone();
+            store_current_index(&mut sysvar_data, i as u16);
+            self.accounts.insert(
+                sysvar::instructions::ID,
+                AccountState {
+                    lamports: 1,
+                    data: sysvar_data,
+                    owner: sysvar::ID,
+                    executable: false,
+                },
+            );
+
+            STATE.with(|s| {
+                let mut s = s.borrow_mut();
+                s.clock = Clock {
+                    slot: self.slot,
+                    unix_timestamp: self.now,
+                    ..Clock::default()
+                };
+                s.return_data = None;
+                // Left over from an instruction that failed.
+                s.logs.clear();
+                s.cpis.clear();
+            });
+            self.process(ix)?;
+            STATE.with(|s| {
+                let mut s = s.borrow_mut();
+                result.logs.append(&mut s.logs);
+                result.cpis.append(&mut s.cpis);
+                result.return_data = s.return_data.take().map(|(_, d)| d);
+            });
+        }
+        self.accounts.remove(&sysvar::instructions::ID);
+        Ok(result)
+    }
+
+    fn process(&mut self, ix: &Instruction) -> std::result::Result<(), ProgramError> {
+        // Merge duplicate metas the way the runtime does.
+        let mut keys: Vec<(Pubkey, bool, bool)> = Vec::new();
+        let mut positions = Vec::with_capacity(ix.accounts.len());
+        for meta in &ix.accounts {
+            match keys.iter_mut().position(|(k, _, _)| *k == meta.pubkey) {
+                Some(p) => {
+                    keys[p].1 |= meta.is_signer;
+                    keys[p].2 |= meta.is_writable;
+                    positions.push(p);
+                }
+                None => {
+                    keys.push((meta.pubkey, meta.is_signer, meta.is_writable));
+                    positions.push(keys.len() - 1);
+                }
+            }
+        }
+
+        let before: Vec<AccountState> = keys
+            .iter()
+            .map(|(k, _, _)| {
+                self.accounts.get(k).cloned().unwrap_or(AccountState {
+                    owner: system_program::ID,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let mut input = Input::default();
+        input.u64(ix.accounts.len() as u64);
+        let mut first_seen = vec![None; keys.len()];
+        for (i, &p) in positions.iter().enumerate() {
+            if let Some(first) = first_seen[p] {
+                input.bytes(&[first as u8]);
+                input.bytes(&[0; 7]);
+                continue;
+            }
+            first_seen[p] = Some(i);
+            let (key, is_signer, is_writable) = keys[p];
+            let account = &before[p];
+            input.bytes(&[
+                NON_DUP_MARKER,
+                is_signer as u8,
+                is_writable as u8,
+                account.executable as u8,
+            ]);
+            input.bytes(&[0; 4]);
+            input.bytes(key.as_ref());
+            input.bytes(account.owner.as_ref());
+            input.u64(account.lamports);
+            input.u64(account.data.len() as u64);
+            input.bytes(&account.data);
+            input.bytes(&[0; MAX_PERMITTED_DATA_INCREASE]);
+            input.align();
+            input.u64(0);
+        }
+        input.u64(ix.data.len() as u64);
+        input.bytes(&ix.data);
+        input.bytes(erc8004_svm::ID.as_ref());
+
+        let mut buffer = input.into_aligned();
+        let after: Vec<AccountState> = {
+            let (program_id, infos, data) =
+                unsafe { entrypoint::deserialize(buffer.as_mut_ptr() as *mut u8) };
+            erc8004_svm::entry(program_id, &infos, data)?;
+
+            first_seen
+                .iter()
+                .map(|first| {
+                    let info = &infos[first.unwrap()];
+                    AccountState {
+                        lamports: info.lamports(),
+                        data: info.data.borrow().to_vec(),
+                        owner: *info.owner,
+                        executable: info.executable,
+                    }
+                })
+                .collect()
+        };
+
+        let total =
+            |states: &[AccountState]| -> u128 { states.iter().map(|a| a.lamports as u128).sum() };
+        assert_eq!(total(&before), total(&after), "lamports not conserved");
+
+        for (p, (key, _, is_writable)) in keys.iter().enumerate() {
+            if after[p] == before[p] {
+                continue;
+            }
+            assert!(is_writable, "read-only account {key} modified");
+            if after[p].lamports == 0 {
+                self.accounts.remove(key);
+            } else {
+                self.accounts.insert(*key, after[p].clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Loader input buffer; backed by `u64`s so the serialized accounts are
+/// aligned like they are on chain.
+#[derive(Default)]
+struct Input(Vec<u8>);
+
+impl Input {
+    fn bytes(&mut self, b: &[u8]) {
+        self.0.extend_from_slice(b);
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn align(&mut self) {
+        self.0.resize(self.0.len().next_multiple_of(8), 0);
+    }
+
+    fn into_aligned(mut self) -> Vec<u64> {
+        self.align();
+        self.0
+            .chunks(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect()
+    }
+}
+
+pub fn ix(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining: &[AccountMeta],
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend_from_slice(remaining);
+    Instruction {
+        program_id: erc8004_svm::ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+/// An ed25519 instruction in the layout `verify_ed25519` reads.
+pub fn ed25519_ix(signer: &Pubkey, msg: &[u8; 32], sig: &[u8; 64]) -> Instruction {
+    let (sig_off, msg_off, pub_off) = (16u16, 80u16, 112u16);
+    let mut data = vec![1u8, 0];
+    for v in [64u16, 32, sig_off, msg_off, pub_off, 0, 0] {
+        data.extend_from_slice(&v.to_le_bytes());
+    }
+    data.extend_from_slice(sig);
+    data.extend_from_slice(msg);
+    data.extend_from_slice(signer.as_ref());
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+pub fn error(e: erc8004_svm::E8004) -> ProgramError {
+    anchor_lang::error::Error::from(e).into()
+}
+
+pub fn anchor_error(e: anchor_lang::error::ErrorCode) -> ProgramError {
+    anchor_lang::error::Error::from(e).into()
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &erc8004_svm::ID).0
+}
+
+pub const HALF_LIFE: i64 = 30 * 86_400;
+
+pub fn tag(name: &str) -> [u8; 32] {
+    erc8004_svm::util::keccak_bytes(name.as_bytes())
+}
+
+/// Feedback parameters for `World::give`.
+#[derive(Clone, Default)]
+pub struct Give {
+    pub score: u8,
+    /// Gives value feedback instead of a score when set.
+    pub value: Option<(i128, u8)>,
+    pub tag1: [u8; 32],
+    pub tag2: [u8; 32],
+    pub tag1_label: Option<String>,
+    pub tag2_label: Option<String>,
+    /// Inline payload; the file hash defaults to its keccak hash.
+    pub payload: Option<Vec<u8>>,
+    pub file_hash: Option<[u8; 32]>,
+}
+
+impl Give {
+    pub fn score(score: u8) -> Self {
+        Give {
+            score,
+            ..Default::default()
+        }
+    }
+
+    pub fn value(value: i128, decimals: u8) -> Self {
+        Give {
+            value: Some((value, decimals)),
+            ..Default::default()
+        }
+    }
+
+    pub fn tags(mut self, tag1: [u8; 32], tag2: [u8; 32]) -> Self {
+        self.tag1 = tag1;
+        self.tag2 = tag2;
+        self
+    }
+
+    pub fn labels(mut self, tag1: &str, tag2: &str) -> Self {
+        self.tag1_label = Some(tag1.into());
+        self.tag2_label = Some(tag2.into());
+        self
+    }
+
+    pub fn payload(mut self, payload: &[u8]) -> Self {
+        self.payload = Some(payload.to_vec());
+        self
+    }
+}
+
+/// A platform with a reputation config and one registered agent.
+pub struct World {
+    pub svm: Svm,
+    pub authority: Pubkey,
+    pub platform: Pubkey,
+    pub owner: Pubkey,
+    pub agent: Pubkey,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        let mut world = Self::without_config();
+        world.set_config(HALF_LIFE).unwrap();
+        world
+    }
+
+    pub fn without_config() -> Self {
+        let mut svm = Svm::new();
+        let authority = svm.funded_keypair();
+        let platform = pda(&[b"platform"]);
+        svm.call(
+            erc8004_svm::accounts::InitPlatformCtx {
+                platform,
+                authority,
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::InitPlatform {},
+            &[],
+        )
+        .unwrap();
+
+        let mut world = World {
+            svm,
+            authority,
+            platform,
+            owner: Pubkey::default(),
+            agent: Pubkey::default(),
+        };
+        let owner = world.svm.funded_keypair();
+        world.agent = world.register_agent(&owner);
+        world.owner = owner;
+        world
+    }
+
+    pub fn register_agent(&mut self, owner: &Pubkey) -> Pubkey {
+        let platform: erc8004_svm::identity::Platform = self.svm.get(&self.platform);
+        let agent = pda(&[
+            b"agent",
+            self.platform.as_ref(),
+            &platform.agent_counter.to_le_bytes(),
+        ]);
+        self.svm
+            .call(
+                erc8004_svm::accounts::AgentRegisterCtx {
+                    platform: self.platform,
+                    authority: self.authority,
+                    owner: *owner,
+                    agent,
+                    system_program: system_program::ID,
+                    mint: None,
+                },
+                erc8004_svm::instruction::AgentRegister {
+                    token_uri: "ipfs://agent".into(),
+                },
+                &[],
+            )
+            .unwrap();
+        agent
+    }
+
+    pub fn set_config(&mut self, half_life: i64) -> std::result::Result<TxResult, ProgramError> {
+        self.configure(half_life, 0)
+    }
+
+    /// Sets the reputation config, with the platform authority as arbiter.
+    pub fn configure(
+        &mut self,
+        half_life: i64,
+        stake_cooldown: i64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.configure_stake_mint(half_life, stake_cooldown, None)
+    }
+
+    pub fn configure_stake_mint(
+        &mut self,
+        half_life: i64,
+        stake_cooldown: i64,
+        stake_mint: Option<Pubkey>,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::SetReputationConfigCtx {
+                platform: self.platform,
+                authority: self.authority,
+                config: pda(&[b"rcfg"]),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::SetReputationConfig {
+                half_life,
+                stake_mint,
+                stake_cooldown,
+                arbiter: self.authority,
+            },
+            &[],
+        )
+    }
+
+    pub fn summary_key(&self) -> Pubkey {
+        pda(&[b"rsum", self.agent.as_ref()])
+    }
+
+    pub fn summary(&self) -> erc8004_svm::reputation::ReputationSummary {
+        self.svm.get(&self.summary_key())
+    }
+
+    pub fn tag_summary_key(&self, tag: &[u8; 32]) -> Pubkey {
+        pda(&[b"tsum", self.agent.as_ref(), tag])
+    }
+
+    pub fn tag_summary(&self, tag: &[u8; 32]) -> erc8004_svm::reputation::TagSummary {
+        self.svm.get(&self.tag_summary_key(tag))
+    }
+
+    pub fn tag_scores(&mut self, tags: &[[u8; 32]]) -> Vec<erc8004_svm::reputation::TagScore> {
+        let remaining: Vec<_> = tags
+            .iter()
+            .map(|t| AccountMeta::new_readonly(self.tag_summary_key(t), false))
+            .collect();
+        self.svm
+            .call(
+                erc8004_svm::accounts::GetTagSummariesCtx { agent: self.agent },
+                erc8004_svm::instruction::GetTagSummaries {
+                    tags: tags.to_vec(),
+                },
+                &remaining,
+            )
+            .unwrap()
+            .returned()
+    }
+
+    pub fn feedback_key(&self, client: &Pubkey, index: u64) -> Pubkey {
+        pda(&[
+            b"fb",
+            self.agent.as_ref(),
+            client.as_ref(),
+            &index.to_le_bytes(),
+        ])
+    }
+
+    pub fn feedback(&self, client: &Pubkey, index: u64) -> erc8004_svm::reputation::Feedback {
+        self.svm.get(&self.feedback_key(client, index))
+    }
+
+    /// Writable metas for the summaries of the non-zero tags.
+    pub fn tag_metas(&self, tags: &[[u8; 32]]) -> Vec<AccountMeta> {
+        let mut tags: Vec<_> = tags.iter().filter(|t| **t != [0u8; 32]).collect();
+        tags.sort();
+        tags.dedup();
+        tags.into_iter()
+            .map(|t| AccountMeta::new(self.tag_summary_key(t), false))
+            .collect()
+    }
+
+    /// A signed FeedbackAuth for `client` and the matching ed25519
+    /// instruction.
+    pub fn feedback_auth(&self, client: &Pubkey) -> (Vec<u8>, [u8; 64], Instruction) {
+        let agent: erc8004_svm::identity::Agent = self.svm.get(&self.agent);
+        let auth = erc8004_svm::util::FeedbackAuth {
+            agent_id: agent.id,
+            client: *client,
+            index_limit: 1_000,
+            expiry: (self.svm.now + 3_600) as u64,
+            chain_id: 0,
+            identity_registry: erc8004_svm::ID,
+            signer: agent.owner,
+        };
+        let word = |v: u64| {
+            let mut w = [0u8; 32];
+            w[24..].copy_from_slice(&v.to_be_bytes());
+            w
+        };
+        let bytes = [
+            word(auth.agent_id),
+            auth.client.to_bytes(),
+            word(auth.index_limit),
+            word(auth.expiry),
+            word(auth.chain_id),
+            auth.identity_registry.to_bytes(),
+            auth.signer.to_bytes(),
+        ]
+        .concat();
+        let signature = [7u8; 64];
+        let digest = erc8004_svm::util::keccak_auth_struct(&auth);
+        let ed_ix = ed25519_ix(&auth.signer, &digest, &signature);
+        (bytes, signature, ed_ix)
+    }
+
+    /// Gives feedback from `client` and returns its index.
+    pub fn give(&mut self, client: &Pubkey, give: Give) -> std::result::Result<u64, ProgramError> {
+        self.give_tx(client, give).map(|(index, _)| index)
+    }
+
+    /// `give`, also returning the transaction result for its events.
+    pub fn give_tx(
+        &mut self,
+        client: &Pubkey,
+        give: Give,
+    ) -> std::result::Result<(u64, TxResult), ProgramError> {
+        let idx = pda(&[b"idx", self.agent.as_ref(), client.as_ref()]);
+        let index = if self.svm.exists(&idx) {
+            self.svm
+                .get::<erc8004_svm::reputation::ClientIndex>(&idx)
+                .last_index
+                + 1
+        } else {
+            1
+        };
+        let client_count = if self.svm.exists(&self.summary_key()) {
+            self.summary().client_count
+        } else {
+            0
+        };
+        let page = (client_count / erc8004_svm::reputation::CLIENTS_PER_PAGE as u64) as u32;
+
+        let mut remaining = self.tag_metas(&[give.tag1, give.tag2]);
+        remaining.push(AccountMeta::new(
+            pda(&[b"clients", self.agent.as_ref(), &page.to_le_bytes()]),
+            false,
+        ));
+
+        let (auth, signature, ed_ix) = self.feedback_auth(client);
+        let accounts = erc8004_svm::accounts::GiveFeedbackCtx {
+            agent: self.agent,
+            client: *client,
+            idx,
+            feedback: self.feedback_key(client, index),
+            summary: self.summary_key(),
+            config: pda(&[b"rcfg"]),
+            ix_sysvar: sysvar::instructions::ID,
+            system_program: system_program::ID,
+        };
+        let file_hash = give.file_hash.unwrap_or(match &give.payload {
+            Some(payload) => erc8004_svm::util::keccak_bytes(payload),
+            None => [1u8; 32],
+        });
+        let program_ix = match give.value {
+            None => ix(
+                accounts,
+                erc8004_svm::instruction::GiveFeedbackEd25519 {
+                    score: give.score,
+                    tag1: give.tag1,
+                    tag2: give.tag2,
+                    file_uri: "ipfs://feedback".into(),
+                    file_hash,
+                    auth_struct_bytes: auth,
+                    signature: signature.to_vec(),
+                    expected_index: index,
+                    tag1_label: give.tag1_label,
+                    tag2_label: give.tag2_label,
+                    payload: give.payload,
+                },
+                &remaining,
+            ),
+            Some((value, value_decimals)) => ix(
+                accounts,
+                erc8004_svm::instruction::GiveFeedbackValueEd25519 {
+                    value,
+                    value_decimals,
+                    tag1: give.tag1,
+                    tag2: give.tag2,
+                    file_uri: "ipfs://feedback".into(),
+                    file_hash,
+                    auth_struct_bytes: auth,
+                    signature: signature.to_vec(),
+                    expected_index: index,
+                    tag1_label: give.tag1_label,
+                    tag2_label: give.tag2_label,
+                    payload: give.payload,
+                },
+                &remaining,
+            ),
+        };
+        let result = self.svm.send(&[ed_ix, program_ix])?;
+        Ok((index, result))
+    }
+
+    pub fn close(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let tombstone = pda(&[
+            b"fbt",
+            self.agent.as_ref(),
+            client.as_ref(),
+            &index.to_le_bytes(),
+        ]);
+        self.svm.call(
+            erc8004_svm::accounts::CloseFeedbackCtx {
+                feedback: self.feedback_key(client, index),
+                dispute: self.dispute_key(client, index),
+                tombstone,
+                client: *client,
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::CloseFeedback {},
+            &[],
+        )
+    }
+
+    pub fn stake_key(&self, client: &Pubkey, index: u64) -> Pubkey {
+        pda(&[b"fstake", self.feedback_key(client, index).as_ref()])
+    }
+
+    pub fn dispute_key(&self, client: &Pubkey, index: u64) -> Pubkey {
+        pda(&[b"dispute", self.feedback_key(client, index).as_ref()])
+    }
+
+    /// Has the client amend scored feedback to `amend`'s score and tags.
+    pub fn amend(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+        amend: Give,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let old = self.feedback(client, index);
+        let summary = self.summary_key();
+        let remaining = self.tag_metas(&[old.tag1, old.tag2, amend.tag1, amend.tag2]);
+        self.svm.call(
+            erc8004_svm::accounts::AmendFeedbackCtx {
+                feedback: self.feedback_key(client, index),
+                dispute: self.dispute_key(client, index),
+                summary: self.svm.exists(&summary).then_some(summary),
+                config: pda(&[b"rcfg"]),
+                client: *client,
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::AmendFeedback {
+                score: amend.score,
+                tag1: amend.tag1,
+                tag2: amend.tag2,
+                file_uri: "ipfs://amended".into(),
+                file_hash: amend.file_hash.unwrap_or([4; 32]),
+                tag1_label: amend.tag1_label,
+                tag2_label: amend.tag2_label,
+            },
+            &remaining,
+        )
+    }
+
+    /// Has the agent owner dispute feedback.
+    pub fn open_dispute(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::OpenDisputeCtx {
+                agent: self.agent,
+                feedback: self.feedback_key(client, index),
+                owner: self.owner,
+                dispute: self.dispute_key(client, index),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::OpenDispute {
+                evidence_uri: "ipfs://evidence".into(),
+                evidence_hash: [3; 32],
+            },
+            &[],
+        )
+    }
+
+    /// Has the arbiter, the platform authority, settle a dispute.
+    pub fn resolve_dispute(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+        strike: bool,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let feedback = self.feedback(client, index);
+        let summary = self.summary_key();
+        let remaining = self.tag_metas(&[feedback.tag1, feedback.tag2]);
+        self.svm.call(
+            erc8004_svm::accounts::ResolveDisputeCtx {
+                dispute: self.dispute_key(client, index),
+                feedback: self.feedback_key(client, index),
+                summary: self.svm.exists(&summary).then_some(summary),
+                config: pda(&[b"rcfg"]),
+                arbiter: self.authority,
+            },
+            erc8004_svm::instruction::ResolveDispute { strike },
+            &remaining,
+        )
+    }
+
+    /// Stakes `amount` lamports behind feedback.
+    pub fn stake(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+        amount: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.stake_tokens(client, index, amount, None)
+    }
+
+    /// Stakes `amount` behind feedback, in tokens of `mint` out of
+    /// `client_token` when `spl` is `Some((mint, client_token))`.
+    pub fn stake_tokens(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+        amount: u64,
+        spl: Option<(Pubkey, Pubkey)>,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let summary = self.summary_key();
+        let feedback = self.feedback_key(client, index);
+        self.svm.call(
+            erc8004_svm::accounts::StakeFeedbackCtx {
+                feedback,
+                summary: self.svm.exists(&summary).then_some(summary),
+                config: pda(&[b"rcfg"]),
+                stake: self.stake_key(client, index),
+                client: *client,
+                stake_mint: spl.map(|(mint, _)| mint),
+                client_token: spl.map(|(_, client_token)| client_token),
+                stake_vault: spl.map(|_| pda(&[b"fstake_vault", feedback.as_ref()])),
+                token_program: spl.map(|_| token::ID),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::StakeFeedback { amount },
+            &[],
+        )
+    }
+
+    pub fn unstake(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.unstake_tokens(client, index, None)
+    }
+
+    /// Unstakes an SPL stake into `client_token`.
+    pub fn unstake_tokens(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+        client_token: Option<Pubkey>,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let summary = self.summary_key();
+        let feedback = self.feedback_key(client, index);
+        self.svm.call(
+            erc8004_svm::accounts::UnstakeFeedbackCtx {
+                feedback,
+                dispute: self.dispute_key(client, index),
+                summary: self.svm.exists(&summary).then_some(summary),
+                config: pda(&[b"rcfg"]),
+                stake: self.stake_key(client, index),
+                client: *client,
+                client_token,
+                stake_vault: client_token.map(|_| pda(&[b"fstake_vault", feedback.as_ref()])),
+                token_program: client_token.map(|_| token::ID),
+            },
+            erc8004_svm::instruction::UnstakeFeedback {},
+            &[],
+        )
+    }
+
+    /// Has the arbiter slash the stake behind struck feedback, into
+    /// `treasury_token` for SPL stakes.
+    pub fn slash_stake(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+        treasury_token: Option<Pubkey>,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let summary = self.summary_key();
+        let feedback = self.feedback_key(client, index);
+        self.svm.call(
+            erc8004_svm::accounts::SlashFeedbackStakeCtx {
+                feedback,
+                summary: self.svm.exists(&summary).then_some(summary),
+                config: pda(&[b"rcfg"]),
+                arbiter: self.authority,
+                platform: self.platform,
+                stake: self.stake_key(client, index),
+                client: *client,
+                treasury: self.authority,
+                treasury_token,
+                stake_vault: treasury_token.map(|_| pda(&[b"fstake_vault", feedback.as_ref()])),
+                token_program: treasury_token.map(|_| token::ID),
+            },
+            erc8004_svm::instruction::SlashFeedbackStake {},
+            &[],
+        )
+    }
+
+    pub fn revoke(
+        &mut self,
+        client: &Pubkey,
+        index: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let feedback = self.feedback(client, index);
+        let summary = self.summary_key();
+        let remaining = self.tag_metas(&[feedback.tag1, feedback.tag2]);
+        self.svm.call(
+            erc8004_svm::accounts::RevokeFeedbackCtx {
+                feedback: self.feedback_key(client, index),
+                summary: self.svm.exists(&summary).then_some(summary),
+                config: pda(&[b"rcfg"]),
+                client: *client,
+            },
+            erc8004_svm::instruction::RevokeFeedback { index },
+            &remaining,
+        )
+    }
+}
+
+/// Validation requests and responses.
+impl World {
+    pub fn request_hash(&self, validator: &Pubkey, uri: &str) -> [u8; 32] {
+        let agent: erc8004_svm::identity::Agent = self.svm.get(&self.agent);
+        erc8004_svm::util::keccak_bytes(
+            &[validator.as_ref(), &agent.id.to_le_bytes(), uri.as_bytes()].concat(),
+        )
+    }
+
+    pub fn request_key(&self, validator: &Pubkey, uri: &str) -> Pubkey {
+        let hash = self.request_hash(validator, uri);
+        pda(&[b"vreq", self.agent.as_ref(), &hash])
+    }
+
+    pub fn request(&self, key: &Pubkey) -> erc8004_svm::validation::ValidationRequest {
+        self.svm.get(key)
+    }
+
+    /// Has the agent owner ask `validator` to validate `uri`, escrowing a
+    /// SOL `bounty`.
+    pub fn request_validation(
+        &mut self,
+        validator: &Pubkey,
+        uri: &str,
+        deadline: i64,
+        bounty: u64,
+    ) -> std::result::Result<Pubkey, ProgramError> {
+        let owner = self.owner;
+        self.request_validation_as(&owner, validator, uri, deadline, bounty)
+    }
+
+    /// `request_validation` signed by `signer`, an operator unless it is the
+    /// agent owner.
+    pub fn request_validation_as(
+        &mut self,
+        signer: &Pubkey,
+        validator: &Pubkey,
+        uri: &str,
+        deadline: i64,
+        bounty: u64,
+    ) -> std::result::Result<Pubkey, ProgramError> {
+        self.request_validation_in(signer, validator, uri, deadline, bounty, None)
+    }
+
+    /// Has the agent owner ask `validator` to validate `uri`, escrowing a
+    /// `bounty` of `mint` from its token account. The vault is only passed
+    /// for a non-zero bounty.
+    pub fn request_validation_spl(
+        &mut self,
+        validator: &Pubkey,
+        uri: &str,
+        deadline: i64,
+        bounty: u64,
+        mint: &Pubkey,
+    ) -> std::result::Result<Pubkey, ProgramError> {
+        let owner = self.owner;
+        self.request_validation_in(&owner, validator, uri, deadline, bounty, Some(*mint))
+    }
+
+    fn request_validation_in(
+        &mut self,
+        signer: &Pubkey,
+        validator: &Pubkey,
+        uri: &str,
+        deadline: i64,
+        bounty: u64,
+        mint: Option<Pubkey>,
+    ) -> std::result::Result<Pubkey, ProgramError> {
+        let request = self.request_key(validator, uri);
+        let operator = (*signer != self.owner)
+            .then(|| pda(&[b"operator", self.agent.as_ref(), signer.as_ref()]));
+        let request_hash = self.request_hash(validator, uri);
+        let escrowed = mint.filter(|_| bounty > 0);
+        self.svm.call(
+            erc8004_svm::accounts::ValidationRequestCtx {
+                agent: self.agent,
+                owner: *signer,
+                operator,
+                request,
+                policy: pda(&[b"vpolicy", self.agent.as_ref()]),
+                validator_entry: self.validator_entry(validator),
+                bounty_mint: mint,
+                owner_token: mint.and_then(|m| self.svm.token_account(signer, &m)),
+                bounty_vault: escrowed.map(|_| self.bounty_vault_key(&request)),
+                token_program: escrowed.map(|_| token::ID),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::ValidationRequest {
+                validator: *validator,
+                request_uri: uri.into(),
+                request_hash,
+                deadline,
+                bounty,
+            },
+            &[],
+        )?;
+        Ok(request)
+    }
+
+    pub fn bounty_vault_key(&self, request: &Pubkey) -> Pubkey {
+        pda(&[b"vvault", request.as_ref()])
+    }
+
+    /// The bounty vault, the token program and `owner`'s token account of
+    /// the request's bounty mint, or `None`s for SOL bounties.
+    fn bounty_accounts(
+        &self,
+        request: &Pubkey,
+        owner: &Pubkey,
+    ) -> (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>) {
+        match self.request(request).bounty_mint {
+            Some(mint) => (
+                Some(self.bounty_vault_key(request)),
+                Some(token::ID),
+                self.svm.token_account(owner, &mint),
+            ),
+            None => (None, None, None),
+        }
+    }
+
+    pub fn response_key(&self, request: &Pubkey, seq: u32) -> Pubkey {
+        pda(&[b"vres", request.as_ref(), &seq.to_le_bytes()])
+    }
+
+    pub fn validation_summary_key(&self, validator: Option<&Pubkey>) -> Pubkey {
+        match validator {
+            Some(v) => pda(&[b"vsum", self.agent.as_ref(), v.as_ref()]),
+            None => pda(&[b"vsum", self.agent.as_ref()]),
+        }
+    }
+
+    /// Records `validator`'s next response to `request`.
+    pub fn respond(
+        &mut self,
+        validator: &Pubkey,
+        request: &Pubkey,
+        response: u8,
+        tag: [u8; 32],
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.respond_with(
+            validator,
+            request,
+            erc8004_svm::instruction::ValidationResponse {
+                response,
+                response_uri: "ipfs://response".into(),
+                response_hash: [8; 32],
+                tag,
+                tag_label: None,
+                attestation: None,
+            },
+        )
+    }
+
+    pub fn respond_with(
+        &mut self,
+        validator: &Pubkey,
+        request: &Pubkey,
+        data: erc8004_svm::instruction::ValidationResponse,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let ix = self.respond_ix(validator, request, data, None);
+        self.svm.send(&[ix])
+    }
+
+    /// The response instruction, with the attestation accounts of `key`.
+    pub fn respond_ix(
+        &self,
+        validator: &Pubkey,
+        request: &Pubkey,
+        data: erc8004_svm::instruction::ValidationResponse,
+        attestation_key: Option<Pubkey>,
+    ) -> Instruction {
+        let seq = self.request(request).response_count + 1;
+        let (bounty_vault, token_program, validator_token) =
+            self.bounty_accounts(request, validator);
+        ix(
+            erc8004_svm::accounts::ValidationResponseCtx {
+                request: *request,
+                validator: *validator,
+                response_acc: self.response_key(request, seq),
+                agent_summary: self.validation_summary_key(None),
+                validator_summary: self.validation_summary_key(Some(validator)),
+                policy: pda(&[b"vpolicy", self.agent.as_ref()]),
+                validator_entry: self.validator_entry(validator),
+                attestation_key: attestation_key.map(|k| pda(&[b"attkey", k.as_ref()])),
+                ix_sysvar: attestation_key
+                    .map(|_| anchor_lang::solana_program::sysvar::instructions::ID),
+                validator_token,
+                bounty_vault,
+                token_program,
+                system_program: system_program::ID,
+            },
+            data,
+            &[],
+        )
+    }
+
+    /// Allowlists the ed25519 `key` for attestation reports of `kind`.
+    pub fn register_attestation_key(
+        &mut self,
+        key: &Pubkey,
+        kind: u8,
+        measurement: [u8; 32],
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::RegisterAttestationKeyCtx {
+                platform: self.platform,
+                authority: self.authority,
+                attestation_key: pda(&[b"attkey", key.as_ref()]),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::RegisterAttestationKey {
+                key: *key,
+                kind,
+                measurement,
+            },
+            &[],
+        )
+    }
+
+    /// A report binding `response_hash` to `request`, dated now.
+    pub fn attestation_report(
+        &self,
+        request: &Pubkey,
+        kind: u8,
+        response_hash: [u8; 32],
+    ) -> erc8004_svm::attestation::AttestationReport {
+        let request_hash = self.request(request).request_hash;
+        erc8004_svm::attestation::AttestationReport {
+            kind,
+            measurement: [3; 32],
+            report_data: erc8004_svm::util::keccak_bytes(
+                &[request_hash.as_ref(), response_hash.as_ref()].concat(),
+            ),
+            timestamp: self.svm.now,
+        }
+    }
+
+    /// Responds with `report` signed by the attestation `key`, the signature
+    /// checked by an ed25519 instruction ahead of the response.
+    pub fn respond_attested(
+        &mut self,
+        validator: &Pubkey,
+        request: &Pubkey,
+        key: &Pubkey,
+        report: erc8004_svm::attestation::AttestationReport,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let digest = erc8004_svm::util::keccak_bytes(&report.try_to_vec().unwrap());
+        let ed_ix = ed25519_ix(key, &digest, &[6; 64]);
+        let program_ix = self.respond_ix(validator, request, attested(report), Some(*key));
+        self.svm.send(&[ed_ix, program_ix])
+    }
+
+    /// Reads the validation summary over `validators` and `tag`, passing
+    /// `accounts` (the `vsum` accounts when `tag` is zero).
+    pub fn validation_score(
+        &mut self,
+        validators: &[Pubkey],
+        tag: [u8; 32],
+        accounts: &[Pubkey],
+    ) -> std::result::Result<erc8004_svm::validation::ValidationScore, ProgramError> {
+        let metas: Vec<AccountMeta> = accounts
+            .iter()
+            .map(|a| AccountMeta::new_readonly(*a, false))
+            .collect();
+        Ok(self
+            .svm
+            .call(
+                erc8004_svm::accounts::GetValidationSummaryCtx { agent: self.agent },
+                erc8004_svm::instruction::GetValidationSummary {
+                    validators: validators.to_vec(),
+                    tag,
+                },
+                &metas,
+            )?
+            .returned())
+    }
+
+    pub fn decline(
+        &mut self,
+        validator: &Pubkey,
+        request: &Pubkey,
+        reason: u8,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::ValidationDeclineCtx {
+                request: *request,
+                validator: *validator,
+            },
+            erc8004_svm::instruction::ValidationDecline {
+                reason,
+                reason_uri: "ipfs://reason".into(),
+            },
+            &[],
+        )
+    }
+
+    /// Has `signer` close an unanswered request past its deadline.
+    pub fn expire(
+        &mut self,
+        request: &Pubkey,
+        signer: &Pubkey,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let payer = self.request(request).refund_to(&self.owner);
+        let (bounty_vault, token_program, payer_token) = self.bounty_accounts(request, &payer);
+        self.svm.call(
+            erc8004_svm::accounts::ValidationExpireCtx {
+                agent: self.agent,
+                request: *request,
+                payer,
+                signer: *signer,
+                payer_token,
+                bounty_vault,
+                token_program,
+            },
+            erc8004_svm::instruction::ValidationExpire {},
+            &[],
+        )
+    }
+
+    /// Has `signer` withdraw an unanswered request.
+    pub fn cancel(
+        &mut self,
+        request: &Pubkey,
+        signer: &Pubkey,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let payer = self.request(request).refund_to(&self.owner);
+        let (bounty_vault, token_program, payer_token) = self.bounty_accounts(request, &payer);
+        self.svm.call(
+            erc8004_svm::accounts::ValidationCancelCtx {
+                agent: self.agent,
+                request: *request,
+                payer,
+                signer: *signer,
+                payer_token,
+                bounty_vault,
+                token_program,
+            },
+            erc8004_svm::instruction::ValidationCancel {},
+            &[],
+        )
+    }
+
+    /// Has `signer` close an answered request and all of its responses.
+    pub fn close_request(
+        &mut self,
+        request: &Pubkey,
+        signer: &Pubkey,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let r = self.request(request);
+        let earlier: Vec<AccountMeta> = (1..r.response_count)
+            .map(|seq| AccountMeta::new(self.response_key(request, seq), false))
+            .collect();
+        let payer = r.refund_to(&self.owner);
+        let (bounty_vault, token_program, _) = self.bounty_accounts(request, &payer);
+        self.svm.call(
+            erc8004_svm::accounts::ValidationCloseCtx {
+                agent: self.agent,
+                request: *request,
+                latest_response: r.latest_response,
+                validator: r.validator,
+                payer,
+                signer: *signer,
+                bounty_vault,
+                token_program,
+            },
+            erc8004_svm::instruction::ValidationClose {},
+            &earlier,
+        )
+    }
+}
+
+/// Agent operators.
+impl World {
+    /// Approves `operator` for the agent without a rate limit.
+    pub fn set_operator(
+        &mut self,
+        operator: &Pubkey,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::SetAgentOperatorCtx {
+                agent: self.agent,
+                owner: self.owner,
+                agent_operator: pda(&[b"operator", self.agent.as_ref(), operator.as_ref()]),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::SetAgentOperator {
+                operator: *operator,
+                max_requests: 0,
+                window: 0,
+            },
+            &[],
+        )
+    }
+}
+
+/// Quorum requests.
+impl World {
+    /// Has the agent owner ask `validators` for a quorum on `uri`, passing
+    /// their registry entries for the agent's policy.
+    pub fn request_quorum(
+        &mut self,
+        validators: &[Pubkey],
+        threshold: u8,
+        uri: &str,
+        deadline: i64,
+    ) -> std::result::Result<Pubkey, ProgramError> {
+        let agent: erc8004_svm::identity::Agent = self.svm.get(&self.agent);
+        let mut preimage: Vec<u8> = validators.iter().flat_map(|v| v.to_bytes()).collect();
+        preimage.extend_from_slice(&agent.id.to_le_bytes());
+        preimage.extend_from_slice(uri.as_bytes());
+        let request_hash = erc8004_svm::util::keccak_bytes(&preimage);
+        let request = pda(&[b"vquorum", self.agent.as_ref(), &request_hash]);
+        // Unregistered validators stand in for their own entries.
+        let entries: Vec<AccountMeta> = validators
+            .iter()
+            .map(|v| AccountMeta::new_readonly(self.validator_entry(v).unwrap_or(*v), false))
+            .collect();
+        self.svm.call(
+            erc8004_svm::accounts::ValidationQuorumRequestCtx {
+                agent: self.agent,
+                owner: self.owner,
+                operator: None,
+                request,
+                policy: pda(&[b"vpolicy", self.agent.as_ref()]),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::ValidationQuorumRequest {
+                validators: validators.to_vec(),
+                threshold,
+                request_uri: uri.into(),
+                request_hash,
+                deadline,
+            },
+            &entries,
+        )?;
+        Ok(request)
+    }
+
+    pub fn quorum_response_key(&self, request: &Pubkey, validator: &Pubkey) -> Pubkey {
+        pda(&[b"qres", request.as_ref(), validator.as_ref()])
+    }
+
+    pub fn respond_quorum(
+        &mut self,
+        validator: &Pubkey,
+        request: &Pubkey,
+        response: u8,
+        tag: [u8; 32],
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::ValidationQuorumResponseCtx {
+                request: *request,
+                validator: *validator,
+                response_acc: self.quorum_response_key(request, validator),
+                agent_summary: self.validation_summary_key(None),
+                policy: pda(&[b"vpolicy", self.agent.as_ref()]),
+                validator_entry: self.validator_entry(validator),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::ValidationQuorumResponse {
+                response,
+                response_uri: "ipfs://response".into(),
+                response_hash: [8; 32],
+                tag,
+                tag_label: None,
+            },
+            &[],
+        )
+    }
+
+    /// `(response, validator)` metas for the validators that responded.
+    pub fn quorum_response_metas(&self, request: &Pubkey) -> Vec<AccountMeta> {
+        let r: erc8004_svm::validation::QuorumRequest = self.svm.get(request);
+        r.validators
+            .iter()
+            .filter(|v| self.svm.exists(&self.quorum_response_key(request, v)))
+            .flat_map(|v| {
+                [
+                    AccountMeta::new(self.quorum_response_key(request, v), false),
+                    AccountMeta::new(*v, false),
+                ]
+            })
+            .collect()
+    }
+
+    /// Expires a quorum request as `signer`, closing `responses`.
+    pub fn expire_quorum(
+        &mut self,
+        request: &Pubkey,
+        signer: &Pubkey,
+        responses: &[AccountMeta],
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let r: erc8004_svm::validation::QuorumRequest = self.svm.get(request);
+        self.svm.call(
+            erc8004_svm::accounts::ValidationQuorumExpireCtx {
+                agent: self.agent,
+                request: *request,
+                payer: r.refund_to(&self.owner),
+                signer: *signer,
+            },
+            erc8004_svm::instruction::ValidationQuorumExpire {},
+            responses,
+        )
+    }
+
+    /// Closes a finalized quorum request as `signer`, closing `responses`.
+    pub fn close_quorum(
+        &mut self,
+        request: &Pubkey,
+        signer: &Pubkey,
+        responses: &[AccountMeta],
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let r: erc8004_svm::validation::QuorumRequest = self.svm.get(request);
+        self.svm.call(
+            erc8004_svm::accounts::ValidationQuorumCloseCtx {
+                agent: self.agent,
+                request: *request,
+                payer: r.refund_to(&self.owner),
+                signer: *signer,
+            },
+            erc8004_svm::instruction::ValidationQuorumClose {},
+            responses,
+        )
+    }
+}
+
+/// Validator registry and agent policies.
+impl World {
+    /// The validator's registry entry, if it registered.
+    pub fn validator_entry(&self, validator: &Pubkey) -> Option<Pubkey> {
+        let entry = pda(&[b"validator", validator.as_ref()]);
+        self.svm.exists(&entry).then_some(entry)
+    }
+
+    /// Sets the validator config, staking in `stake_mint` or SOL.
+    pub fn set_validator_config(
+        &mut self,
+        stake_mint: Option<Pubkey>,
+        min_stake: u64,
+        unbond_period: i64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::SetValidatorConfigCtx {
+                platform: self.platform,
+                authority: self.authority,
+                config: pda(&[b"vcfg"]),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::SetValidatorConfig {
+                stake_mint,
+                min_stake,
+                unbond_period,
+            },
+            &[],
+        )
+    }
+
+    /// Registers `validator` with a stake in the configured asset, from its
+    /// token account for SPL stakes, setting up a SOL validator config
+    /// first if there is none.
+    pub fn register_validator(
+        &mut self,
+        validator: &Pubkey,
+        methods: u8,
+        stake: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let config = pda(&[b"vcfg"]);
+        if !self.svm.exists(&config) {
+            self.set_validator_config(None, 0, 0)?;
+        }
+        let mint = self
+            .svm
+            .get::<erc8004_svm::validator::ValidatorConfig>(&config)
+            .stake_mint;
+        self.svm.call(
+            erc8004_svm::accounts::RegisterValidatorCtx {
+                validator: *validator,
+                config,
+                entry: pda(&[b"validator", validator.as_ref()]),
+                stake_mint: mint,
+                validator_token: mint.and_then(|m| self.svm.token_account(validator, &m)),
+                stake_vault: mint.map(|_| pda(&[b"validator_vault", validator.as_ref()])),
+                token_program: mint.map(|_| token::ID),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::RegisterValidator {
+                methods,
+                metadata_uri: "ipfs://validator".into(),
+                amount: stake,
+            },
+            &[],
+        )
+    }
+
+    /// The stake vault, the token program and `owner`'s token account of the
+    /// validator's stake mint, or `None`s for SOL stakes.
+    fn stake_accounts(
+        &self,
+        validator: &Pubkey,
+        owner: &Pubkey,
+    ) -> (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>) {
+        let entry: erc8004_svm::validator::ValidatorEntry =
+            self.svm.get(&pda(&[b"validator", validator.as_ref()]));
+        match entry.stake_mint {
+            Some(mint) => (
+                Some(pda(&[b"validator_vault", validator.as_ref()])),
+                Some(token::ID),
+                self.svm.token_account(owner, &mint),
+            ),
+            None => (None, None, None),
+        }
+    }
+
+    pub fn unbond_validator(
+        &mut self,
+        validator: &Pubkey,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::UpdateValidatorCtx {
+                entry: pda(&[b"validator", validator.as_ref()]),
+                validator: *validator,
+            },
+            erc8004_svm::instruction::UnbondValidator {},
+            &[],
+        )
+    }
+
+    pub fn withdraw_validator(
+        &mut self,
+        validator: &Pubkey,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let (stake_vault, token_program, validator_token) =
+            self.stake_accounts(validator, validator);
+        self.svm.call(
+            erc8004_svm::accounts::WithdrawValidatorStakeCtx {
+                entry: pda(&[b"validator", validator.as_ref()]),
+                config: pda(&[b"vcfg"]),
+                validator: *validator,
+                validator_token,
+                stake_vault,
+                token_program,
+            },
+            erc8004_svm::instruction::WithdrawValidatorStake {},
+            &[],
+        )
+    }
+
+    pub fn set_validation_policy(
+        &mut self,
+        required_methods: u8,
+        min_stake: u64,
+        stake_mint: Option<Pubkey>,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::SetValidationPolicyCtx {
+                agent: self.agent,
+                owner: self.owner,
+                policy: pda(&[b"vpolicy", self.agent.as_ref()]),
+                system_program: system_program::ID,
+            },
+            erc8004_svm::instruction::SetValidationPolicy {
+                require_registered: true,
+                required_methods,
+                min_stake,
+                stake_mint,
+            },
+            &[],
+        )
+    }
+
+    fn is_quorum_response(&self, response: &Pubkey) -> bool {
+        use anchor_lang::Discriminator;
+        self.svm.accounts[response]
+            .data
+            .starts_with(&erc8004_svm::validation::QuorumResponse::DISCRIMINATOR)
+    }
+
+    /// Has the platform authority open or dismiss a challenge against
+    /// `response`, a `ValidationResponse` or a `QuorumResponse`.
+    pub fn challenge(
+        &mut self,
+        response: &Pubkey,
+        challenged: bool,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        self.svm.call(
+            erc8004_svm::accounts::ChallengeResponseCtx {
+                platform: self.platform,
+                authority: self.authority,
+                response: (!self.is_quorum_response(response)).then_some(*response),
+                quorum_response: self.is_quorum_response(response).then_some(*response),
+            },
+            erc8004_svm::instruction::ChallengeResponse { challenged },
+            &[],
+        )
+    }
+
+    /// Has the platform authority slash `validator` over `response`, a
+    /// `ValidationResponse` or a `QuorumResponse`.
+    pub fn slash_validator(
+        &mut self,
+        validator: &Pubkey,
+        response: &Pubkey,
+        amount: u64,
+    ) -> std::result::Result<TxResult, ProgramError> {
+        let (stake_vault, token_program, treasury_token) =
+            self.stake_accounts(validator, &self.authority);
+        self.svm.call(
+            erc8004_svm::accounts::SlashValidatorCtx {
+                platform: self.platform,
+                authority: self.authority,
+                entry: pda(&[b"validator", validator.as_ref()]),
+                response: (!self.is_quorum_response(response)).then_some(*response),
+                quorum_response: self.is_quorum_response(response).then_some(*response),
+                treasury_token,
+                stake_vault,
+                token_program,
+            },
+            erc8004_svm::instruction::SlashValidator {
+                amount,
+                evidence_hash: [9; 32],
+            },
+            &[],
+        )
+    }
+}
+
+/// A response backed by `report`, with the signature `respond_attested`
+/// uses.
+pub fn attested(
+    report: erc8004_svm::attestation::AttestationReport,
+) -> erc8004_svm::instruction::ValidationResponse {
+    erc8004_svm::instruction::ValidationResponse {
+        response: 90,
+        response_uri: "ipfs://response".into(),
+        response_hash: [8; 32],
+        tag: [0; 32],
+        tag_label: None,
+        attestation: Some(erc8004_svm::attestation::Attestation {
+            report,
+            signature: vec![6; 64],
+        }),
+    }
+}
+
+/// `unwrap_err` for results whose value type is not `Debug`.
+pub fn expect_err<T>(result: std::result::Result<T, ProgramError>) -> ProgramError {
+    match result {
+        Ok(_) => panic!("expected the transaction to fail"),
+        Err(e) => e,
+    }
+}
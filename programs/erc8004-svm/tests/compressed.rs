@@ -0,0 +1,416 @@
+mod common;
+
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use common::*;
+use erc8004_svm::compressed::{
+    CompressedFeedback, CompressedFeedbackAppended, CompressedFeedbackRevoked, FeedbackTree,
+    SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID,
+};
+use erc8004_svm::E8004;
+
+fn discriminator(ix: &str) -> Vec<u8> {
+    hash(format!("global:{ix}").as_bytes()).to_bytes()[..8].to_vec()
+}
+
+/// The one spl-account-compression CPI of a transaction.
+fn compression_cpi(result: &TxResult) -> &Instruction {
+    let mut cpis = result
+        .cpis
+        .iter()
+        .filter(|ix| ix.program_id == SPL_ACCOUNT_COMPRESSION_ID);
+    let cpi = cpis.next().expect("no compression CPI");
+    assert!(cpis.next().is_none());
+    cpi
+}
+
+const MAX_DEPTH: u32 = 3;
+const MAX_BUFFER_SIZE: u32 = 8;
+/// spl-account-compression's error for a failed tree operation, such as a
+/// proof that does not verify.
+const INVALID_PROOF: u32 = 6001;
+
+/// A zeroed account owned by spl-account-compression and sized for a tree.
+fn tree_account(w: &mut World) -> Pubkey {
+    let merkle_tree = Pubkey::new_unique();
+    w.svm.accounts.insert(
+        merkle_tree,
+        AccountState {
+            lamports: SOL,
+            data: vec![0; compression::tree_space(MAX_DEPTH, MAX_BUFFER_SIZE)],
+            owner: SPL_ACCOUNT_COMPRESSION_ID,
+            executable: false,
+        },
+    );
+    merkle_tree
+}
+
+/// A tree account initialized and registered with the program.
+fn init_tree(w: &mut World) -> Pubkey {
+    let merkle_tree = tree_account(w);
+    let result = w
+        .svm
+        .call(
+            erc8004_svm::accounts::InitFeedbackTreeCtx {
+                platform: w.platform,
+                authority: w.authority,
+                merkle_tree,
+                tree: pda(&[b"ftree", merkle_tree.as_ref()]),
+                noop: SPL_NOOP_ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                system_program: anchor_lang::system_program::ID,
+            },
+            erc8004_svm::instruction::InitFeedbackTree {
+                max_depth: MAX_DEPTH,
+                max_buffer_size: MAX_BUFFER_SIZE,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let cpi = compression_cpi(&result);
+    let mut data = discriminator("init_empty_merkle_tree");
+    data.extend_from_slice(&MAX_DEPTH.to_le_bytes());
+    data.extend_from_slice(&MAX_BUFFER_SIZE.to_le_bytes());
+    assert_eq!(cpi.data, data);
+    assert_eq!(cpi.accounts[1].pubkey, w.platform);
+    assert!(cpi.accounts[1].is_signer);
+    assert_eq!(
+        compression::tree_root(&w.svm.accounts[&merkle_tree].data),
+        compression::MerkleTree::new(MAX_DEPTH).root()
+    );
+    merkle_tree
+}
+
+fn give_compressed(
+    w: &mut World,
+    client: &Pubkey,
+    merkle_tree: Pubkey,
+    give: Give,
+) -> std::result::Result<TxResult, ProgramError> {
+    let idx = pda(&[b"idx", w.agent.as_ref(), client.as_ref()]);
+    let index = if w.svm.exists(&idx) {
+        w.svm
+            .get::<erc8004_svm::reputation::ClientIndex>(&idx)
+            .last_index
+            + 1
+    } else {
+        1
+    };
+    let mut remaining = w.tag_metas(&[give.tag1, give.tag2]);
+    remaining.push(AccountMeta::new(
+        pda(&[b"clients", w.agent.as_ref(), &0u32.to_le_bytes()]),
+        false,
+    ));
+    let (auth, signature, ed_ix) = w.feedback_auth(client);
+    let program_ix = ix(
+        erc8004_svm::accounts::GiveFeedbackCompressedCtx {
+            agent: w.agent,
+            client: *client,
+            idx,
+            summary: w.summary_key(),
+            config: pda(&[b"rcfg"]),
+            platform: w.platform,
+            tree: pda(&[b"ftree", merkle_tree.as_ref()]),
+            merkle_tree,
+            noop: SPL_NOOP_ID,
+            compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+            ix_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        erc8004_svm::instruction::GiveFeedbackCompressed {
+            score: give.score,
+            tag1: give.tag1,
+            tag2: give.tag2,
+            file_uri: "ipfs://compressed".into(),
+            file_hash: [5; 32],
+            auth_struct_bytes: auth,
+            signature: signature.to_vec(),
+            expected_index: index,
+        },
+        &remaining,
+    );
+    w.svm.send(&[ed_ix, program_ix])
+}
+
+fn revoke_compressed(
+    w: &mut World,
+    client: &Pubkey,
+    merkle_tree: Pubkey,
+    feedback: CompressedFeedback,
+    leaf_index: u32,
+    tag_summaries: &[[u8; 32]],
+    (root, proof): ([u8; 32], &[[u8; 32]]),
+) -> std::result::Result<TxResult, ProgramError> {
+    let mut remaining = w.tag_metas(tag_summaries);
+    remaining.extend(
+        proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(Pubkey::new_from_array(*node), false)),
+    );
+    w.svm.call(
+        erc8004_svm::accounts::RevokeCompressedFeedbackCtx {
+            summary: w.summary_key(),
+            config: pda(&[b"rcfg"]),
+            platform: w.platform,
+            tree: pda(&[b"ftree", merkle_tree.as_ref()]),
+            merkle_tree,
+            noop: SPL_NOOP_ID,
+            compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+            client: *client,
+        },
+        erc8004_svm::instruction::RevokeCompressedFeedback {
+            feedback,
+            root,
+            leaf_index,
+        },
+        &remaining,
+    )
+}
+
+#[test]
+fn compressed_feedback_is_appended_and_counted() {
+    let mut w = World::new();
+    let merkle_tree = init_tree(&mut w);
+    let tree_key = pda(&[b"ftree", merkle_tree.as_ref()]);
+    let a = w.svm.funded_keypair();
+    let speed = tag("speed");
+
+    let result = give_compressed(
+        &mut w,
+        &a,
+        merkle_tree,
+        Give::score(80).tags(speed, [0; 32]),
+    )
+    .unwrap();
+    let ev = result.event::<CompressedFeedbackAppended>();
+    assert_eq!((ev.merkle_tree, ev.leaf_index), (merkle_tree, 0));
+    assert_eq!((ev.feedback.agent, ev.feedback.client), (w.agent, a));
+    assert_eq!((ev.feedback.index, ev.feedback.score), (1, 80));
+    assert_eq!(ev.leaf, ev.feedback.leaf());
+    assert_eq!(ev.file_uri, "ipfs://compressed");
+
+    let append = compression_cpi(&result);
+    assert_eq!(
+        append.data,
+        [discriminator("append"), ev.leaf.to_vec()].concat()
+    );
+    assert_eq!(append.accounts[0].pubkey, merkle_tree);
+
+    let tree: FeedbackTree = w.svm.get(&tree_key);
+    assert_eq!(tree.num_leaves, 1);
+    let mut offchain = compression::MerkleTree::new(MAX_DEPTH);
+    offchain.append(ev.leaf);
+    assert_eq!(
+        compression::tree_root(&w.svm.accounts[&merkle_tree].data),
+        offchain.root()
+    );
+    let s = w.summary();
+    assert_eq!((s.feedback_count, s.score_sum, s.client_count), (1, 80, 1));
+    assert_eq!(w.tag_summary(&speed).score_sum, 80);
+
+    // Indices are shared with account-backed feedback.
+    assert_eq!(w.give(&a, Give::score(10)).unwrap(), 2);
+    let result = give_compressed(&mut w, &a, merkle_tree, Give::score(60)).unwrap();
+    let ev = result.event::<CompressedFeedbackAppended>();
+    assert_eq!((ev.leaf_index, ev.feedback.index), (1, 3));
+    assert_eq!(w.summary().client_count, 1);
+    offchain.append(ev.leaf);
+    assert_eq!(
+        compression::tree_root(&w.svm.accounts[&merkle_tree].data),
+        offchain.root()
+    );
+}
+
+/// Two leaves, `a`'s tagged with `speed` and `b`'s untagged, mirrored in the
+/// returned off-chain tree.
+fn two_leaves(
+    w: &mut World,
+    merkle_tree: Pubkey,
+) -> (Pubkey, Pubkey, CompressedFeedback, compression::MerkleTree) {
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let mut offchain = compression::MerkleTree::new(MAX_DEPTH);
+    let result = give_compressed(
+        w,
+        &a,
+        merkle_tree,
+        Give::score(80).tags(tag("speed"), [0; 32]),
+    )
+    .unwrap();
+    let feedback = result.event::<CompressedFeedbackAppended>().feedback;
+    offchain.append(feedback.leaf());
+    let result = give_compressed(w, &b, merkle_tree, Give::score(40)).unwrap();
+    offchain.append(result.event::<CompressedFeedbackAppended>().leaf);
+    (a, b, feedback, offchain)
+}
+
+#[test]
+fn compressed_feedback_is_revoked_by_its_client() {
+    let mut w = World::new();
+    let merkle_tree = init_tree(&mut w);
+    let (a, b, feedback, mut offchain) = two_leaves(&mut w, merkle_tree);
+    let speed = tag("speed");
+    let (root, proof) = (offchain.root(), offchain.proof(0));
+
+    assert_eq!(
+        revoke_compressed(
+            &mut w,
+            &b,
+            merkle_tree,
+            feedback.clone(),
+            0,
+            &[speed],
+            (root, &proof)
+        )
+        .unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    assert_eq!(
+        revoke_compressed(
+            &mut w,
+            &a,
+            merkle_tree,
+            feedback.clone(),
+            0,
+            &[],
+            (root, &[])
+        )
+        .unwrap_err(),
+        error(E8004::TagSummaryMissing)
+    );
+
+    let result = revoke_compressed(
+        &mut w,
+        &a,
+        merkle_tree,
+        feedback.clone(),
+        0,
+        &[speed],
+        (root, &proof),
+    )
+    .unwrap();
+    let ev = result.event::<CompressedFeedbackRevoked>();
+    assert_eq!((ev.leaf_index, ev.client, ev.index), (0, a, 1));
+
+    let replace = compression_cpi(&result);
+    let data = [
+        discriminator("replace_leaf"),
+        root.to_vec(),
+        feedback.leaf().to_vec(),
+        [0u8; 32].to_vec(),
+        0u32.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    assert_eq!(replace.data, data);
+    // The proof nodes follow the tree, authority and noop accounts.
+    assert_eq!(replace.accounts.len(), 3 + MAX_DEPTH as usize);
+    offchain.replace(0, [0; 32]);
+    assert_eq!(
+        compression::tree_root(&w.svm.accounts[&merkle_tree].data),
+        offchain.root()
+    );
+
+    let s = w.summary();
+    assert_eq!((s.active_count, s.revoked_count, s.score_sum), (1, 1, 40));
+    assert_eq!(w.tag_summary(&speed).active_count, 0);
+
+    // The leaf is gone, so the same feedback cannot be revoked twice.
+    let err = revoke_compressed(
+        &mut w,
+        &a,
+        merkle_tree,
+        feedback,
+        0,
+        &[speed],
+        (offchain.root(), &offchain.proof(0)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ProgramError::Custom(INVALID_PROOF));
+    assert_eq!(w.summary().revoked_count, 1);
+}
+
+#[test]
+fn revoking_needs_a_valid_proof() {
+    let mut w = World::new();
+    let merkle_tree = init_tree(&mut w);
+    let (a, _, feedback, offchain) = two_leaves(&mut w, merkle_tree);
+    let speed = tag("speed");
+    let root = offchain.root();
+
+    // Leaf 1 is `b`'s, so neither its index nor its proof fit.
+    let err = revoke_compressed(
+        &mut w,
+        &a,
+        merkle_tree,
+        feedback.clone(),
+        1,
+        &[speed],
+        (root, &offchain.proof(1)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ProgramError::Custom(INVALID_PROOF));
+
+    // Nor does feedback that differs from the appended leaf.
+    let mut forged = feedback.clone();
+    forged.score = 0;
+    let err = revoke_compressed(
+        &mut w,
+        &a,
+        merkle_tree,
+        forged,
+        0,
+        &[speed],
+        (root, &offchain.proof(0)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ProgramError::Custom(INVALID_PROOF));
+
+    // Nor a tampered proof.
+    let mut proof = offchain.proof(0);
+    proof[1] = [9; 32];
+    let err = revoke_compressed(
+        &mut w,
+        &a,
+        merkle_tree,
+        feedback,
+        0,
+        &[speed],
+        (root, &proof),
+    )
+    .unwrap_err();
+    assert_eq!(err, ProgramError::Custom(INVALID_PROOF));
+
+    let s = w.summary();
+    assert_eq!((s.active_count, s.revoked_count), (2, 0));
+    assert_eq!(w.tag_summary(&speed).active_count, 1);
+}
+
+#[test]
+fn only_the_authority_adds_trees() {
+    let mut w = World::new();
+    let mallory = w.svm.funded_keypair();
+    let merkle_tree = tree_account(&mut w);
+    let err = w
+        .svm
+        .call(
+            erc8004_svm::accounts::InitFeedbackTreeCtx {
+                platform: w.platform,
+                authority: mallory,
+                merkle_tree,
+                tree: pda(&[b"ftree", merkle_tree.as_ref()]),
+                noop: SPL_NOOP_ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                system_program: anchor_lang::system_program::ID,
+            },
+            erc8004_svm::instruction::InitFeedbackTree {
+                max_depth: MAX_DEPTH,
+                max_buffer_size: MAX_BUFFER_SIZE,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintHasOne)
+    );
+}
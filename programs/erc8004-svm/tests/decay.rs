@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use erc8004_svm::reputation::{DecayedScore, DEFAULT_HALF_LIFE};
+use erc8004_svm::util::{decay_factor, DECAY_SCALE};
+
+#[test]
+fn decay_factor_halves_every_half_life() {
+    let h = 1_000;
+    assert_eq!(decay_factor(0, h), DECAY_SCALE);
+    assert_eq!(decay_factor(-5, h), DECAY_SCALE);
+    assert_eq!(decay_factor(h, h), DECAY_SCALE / 2);
+    assert_eq!(decay_factor(3 * h, h), DECAY_SCALE / 8);
+    assert_eq!(decay_factor(64 * h, h), 0);
+    assert_eq!(decay_factor(10, 0), DECAY_SCALE);
+
+    // 2^(-1/2) and 2^(-5/4) to within the Taylor series' precision.
+    let close = |got: u128, want: f64| {
+        let want = want * DECAY_SCALE as f64;
+        assert!((got as f64 - want).abs() < 10.0, "{got} vs {want}");
+    };
+    close(decay_factor(h / 2, h), 0.5f64.sqrt());
+    close(decay_factor(5 * h / 4, h), 2f64.powf(-1.25));
+
+    let mut last = DECAY_SCALE;
+    for t in (0..5 * h).step_by(37) {
+        let f = decay_factor(t, h);
+        assert!(f <= last);
+        last = f;
+    }
+}
+
+fn decayed_score(w: &mut World) -> DecayedScore {
+    let summary = w.summary_key();
+    w.svm
+        .call(
+            erc8004_svm::accounts::GetDecayedScoreCtx {
+                summary,
+                config: pda(&[b"rcfg"]),
+            },
+            erc8004_svm::instruction::GetDecayedScore {},
+            &[],
+        )
+        .unwrap()
+        .returned()
+}
+
+#[test]
+fn decayed_score_weights_recent_feedback() {
+    let mut w = World::new();
+    w.set_config(1_000).unwrap();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(20)).unwrap();
+    w.svm.warp(1_000);
+    w.give(&a, Give::score(80)).unwrap();
+
+    // (20 * 0.5 + 80) / 1.5
+    let s = decayed_score(&mut w);
+    assert_eq!(s.weight, DECAY_SCALE + DECAY_SCALE / 2);
+    assert_eq!(s.average_score, 60);
+
+    // Ageing both leaves the average alone and shrinks the weight.
+    w.svm.warp(2_000);
+    let s = decayed_score(&mut w);
+    assert_eq!(s.weight, (DECAY_SCALE + DECAY_SCALE / 2) / 4);
+    assert_eq!(s.average_score, 60);
+}
+
+#[test]
+fn feedback_works_before_config_is_set() {
+    let mut w = World::without_config();
+    assert!(!w.svm.exists(&pda(&[b"rcfg"])));
+    let a = w.svm.funded_keypair();
+
+    w.give(&a, Give::score(40)).unwrap();
+    w.svm.warp(DEFAULT_HALF_LIFE);
+    w.give(&a, Give::score(70)).unwrap();
+    assert_eq!(w.summary().decayed_weight, DECAY_SCALE + DECAY_SCALE / 2);
+
+    let s = decayed_score(&mut w);
+    assert_eq!(s.average_score, 60);
+
+    w.revoke(&a, 2).unwrap();
+    assert_eq!(w.summary().decayed_weight, DECAY_SCALE / 2);
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::*;
+use erc8004_svm::dispute::{Dispute, DisputeResolved, DisputeStatus};
+use erc8004_svm::E8004;
+
+#[test]
+fn open_dispute_keeps_feedback_and_stake() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(10)).unwrap();
+    w.stake(&a, 1, SOL).unwrap();
+    w.open_dispute(&a, 1).unwrap();
+
+    let d: Dispute = w.svm.get(&w.dispute_key(&a, 1));
+    assert!(d.status == DisputeStatus::Open);
+    assert_eq!((d.agent, d.opened_by), (w.agent, w.owner));
+
+    assert_eq!(w.unstake(&a, 1).unwrap_err(), error(E8004::StakeLocked));
+
+    // Revoking is still allowed, but the feedback stays for the arbiter.
+    w.revoke(&a, 1).unwrap();
+    assert_eq!(w.close(&a, 1).unwrap_err(), error(E8004::StakeLocked));
+
+    w.resolve_dispute(&a, 1, false).unwrap();
+    w.unstake(&a, 1).unwrap();
+    w.close(&a, 1).unwrap();
+    assert!(!w.svm.exists(&w.feedback_key(&a, 1)));
+}
+
+#[test]
+fn revoked_feedback_under_dispute_cannot_close() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(10)).unwrap();
+    w.open_dispute(&a, 1).unwrap();
+    w.revoke(&a, 1).unwrap();
+
+    assert_eq!(w.close(&a, 1).unwrap_err(), error(E8004::DisputeOpen));
+
+    let ev: DisputeResolved = w.resolve_dispute(&a, 1, true).unwrap().event();
+    assert!(ev.struck);
+    let d: Dispute = w.svm.get(&w.dispute_key(&a, 1));
+    assert!(d.status == DisputeStatus::Struck);
+    w.close(&a, 1).unwrap();
+}
+
+#[test]
+fn striking_takes_feedback_out_of_summaries() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let speed = tag("speed");
+    w.give(&a, Give::score(10).tags(speed, [0; 32])).unwrap();
+    w.give(&a, Give::score(90)).unwrap();
+    w.open_dispute(&a, 1).unwrap();
+
+    w.resolve_dispute(&a, 1, true).unwrap();
+    assert!(w.feedback(&a, 1).struck);
+    let s = w.summary();
+    assert_eq!((s.active_count, s.score_sum), (1, 90));
+    assert_eq!(w.tag_summary(&speed).active_count, 0);
+
+    assert_eq!(
+        w.resolve_dispute(&a, 1, false).unwrap_err(),
+        error(E8004::DisputeClosed)
+    );
+    assert!(w.open_dispute(&a, 1).is_err(), "disputed once");
+}
+
+#[test]
+fn amend_waits_for_an_open_dispute() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(10)).unwrap();
+    w.open_dispute(&a, 1).unwrap();
+
+    assert_eq!(
+        w.amend(&a, 1, Give::score(60)).unwrap_err(),
+        error(E8004::DisputeOpen)
+    );
+    assert_eq!(w.feedback(&a, 1).revision, 0);
+
+    w.resolve_dispute(&a, 1, false).unwrap();
+    w.amend(&a, 1, Give::score(60)).unwrap();
+    assert_eq!(w.feedback(&a, 1).score, 60);
+}
+
+#[test]
+fn struck_spl_stake_goes_to_the_treasury() {
+    let mut w = World::new();
+    let mint = w.svm.create_mint();
+    w.configure_stake_mint(HALF_LIFE, 0, Some(mint)).unwrap();
+    let a = w.svm.funded_keypair();
+    let wallet = w.svm.create_token_account(&mint, &a, 500);
+    let treasury = w.svm.create_token_account(&mint, &w.authority, 0);
+    w.give(&a, Give::score(10)).unwrap();
+    w.stake_tokens(&a, 1, 500, Some((mint, wallet))).unwrap();
+
+    w.open_dispute(&a, 1).unwrap();
+    w.resolve_dispute(&a, 1, true).unwrap();
+    assert_eq!(
+        w.unstake_tokens(&a, 1, Some(wallet)).unwrap_err(),
+        error(E8004::StakeLocked)
+    );
+
+    // SPL stakes only go to a token account of the platform authority.
+    let elsewhere = w.svm.create_token_account(&mint, &a, 0);
+    assert_eq!(
+        w.slash_stake(&a, 1, Some(elsewhere)).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintTokenOwner)
+    );
+    let vault = pda(&[b"fstake_vault", w.feedback_key(&a, 1).as_ref()]);
+    w.slash_stake(&a, 1, Some(treasury)).unwrap();
+    assert_eq!(w.svm.token_balance(&treasury), 500);
+    assert_eq!(w.svm.token_balance(&wallet), 0);
+    assert!(!w.svm.exists(&vault));
+    assert!(!w.svm.exists(&w.stake_key(&a, 1)));
+}
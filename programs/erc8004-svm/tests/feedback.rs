@@ -0,0 +1,280 @@
+mod common;
+
+use anchor_lang::Discriminator;
+use common::*;
+use erc8004_svm::reputation::{
+    Feedback, FeedbackAmended, FeedbackClosed, FeedbackMigrated, FeedbackPayload, FeedbackRevoked,
+    FeedbackTombstone, MAX_FILE_URI, MAX_INLINE_PAYLOAD,
+};
+use erc8004_svm::E8004;
+
+#[test]
+fn feedback_records_time_and_slot() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let (given_at, given_slot) = (w.svm.now, w.svm.slot);
+    w.give(&a, Give::score(50)).unwrap();
+
+    let f = w.feedback(&a, 1);
+    assert_eq!((f.created_at, f.created_slot), (given_at, given_slot));
+    assert_eq!((f.revoked_at, f.revoked_slot), (0, 0));
+
+    w.svm.warp(600);
+    let ev: FeedbackRevoked = w.revoke(&a, 1).unwrap().event();
+    let f = w.feedback(&a, 1);
+    assert_eq!((f.revoked_at, f.revoked_slot), (w.svm.now, w.svm.slot));
+    assert_eq!((ev.revoked_at, ev.revoked_slot), (w.svm.now, w.svm.slot));
+    assert_eq!(f.created_at, given_at);
+}
+
+const V0_SPACE: usize = 8 + 32 + 32 + 8 + 1 + 32 + 32 + 1 + 4 + MAX_FILE_URI + 32 + 1;
+
+fn put_v0(w: &mut World, client: &Pubkey) -> Pubkey {
+    let key = w.feedback_key(client, 1);
+    // `Feedback` as it was laid out before timestamps were recorded.
+    let uri = "ipfs://old";
+    let data = [
+        &Feedback::DISCRIMINATOR[..],
+        w.agent.as_ref(),
+        client.as_ref(),
+        &1u64.to_le_bytes(),
+        &[70],
+        &[0; 32],
+        &[0; 32],
+        &[0],
+        &(uri.len() as u32).to_le_bytes(),
+        uri.as_bytes(),
+        &[2; 32],
+        &[255],
+    ];
+    let mut data = data.concat();
+    data.resize(V0_SPACE, 0);
+    w.svm.accounts.insert(
+        key,
+        AccountState {
+            lamports: Rent::default().minimum_balance(V0_SPACE),
+            data,
+            owner: erc8004_svm::ID,
+            executable: false,
+        },
+    );
+    key
+}
+
+fn migrate(w: &mut World, feedback: Pubkey, payer: Pubkey) -> Result<TxResult, ProgramError> {
+    w.svm.call(
+        erc8004_svm::accounts::MigrateFeedbackCtx {
+            feedback,
+            payer,
+            system_program: anchor_lang::system_program::ID,
+        },
+        erc8004_svm::instruction::MigrateFeedback {},
+        &[],
+    )
+}
+
+#[test]
+fn migrate_grows_old_feedback() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let key = put_v0(&mut w, &a);
+    let payer = w.svm.funded_keypair();
+
+    let ev: FeedbackMigrated = migrate(&mut w, key, payer).unwrap().event();
+    assert_eq!(
+        (ev.feedback, ev.old_len, ev.new_len),
+        (key, V0_SPACE as u32, Feedback::SPACE as u32)
+    );
+    assert_eq!(
+        w.svm.lamports(&key),
+        Rent::default().minimum_balance(Feedback::SPACE)
+    );
+
+    let f = w.feedback(&a, 1);
+    assert_eq!((f.score, f.file_uri.as_str()), (70, "ipfs://old"));
+    assert_eq!((f.created_at, f.created_slot), (0, 0));
+    assert!(!f.counted);
+
+    assert_eq!(
+        migrate(&mut w, key, payer).unwrap_err(),
+        error(E8004::AlreadyMigrated)
+    );
+
+    // It never reached the summary, so revoking it leaves the summary alone.
+    let b = w.svm.funded_keypair();
+    w.give(&b, Give::score(10)).unwrap();
+    w.revoke(&a, 1).unwrap();
+    let s = w.summary();
+    assert_eq!((s.active_count, s.revoked_count, s.score_sum), (1, 0, 10));
+}
+
+#[test]
+fn close_reclaims_revoked_feedback() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(50)).unwrap();
+
+    assert_eq!(w.close(&a, 1).unwrap_err(), error(E8004::NotRevoked));
+
+    w.svm.warp(60);
+    w.revoke(&a, 1).unwrap();
+    let revoked_at = w.svm.now;
+    w.svm.warp(60);
+    let key = w.feedback_key(&a, 1);
+    let tombstone_key = pda(&[b"fbt", w.agent.as_ref(), a.as_ref(), &1u64.to_le_bytes()]);
+    let before = w.svm.lamports(&a);
+    let feedback_rent = w.svm.lamports(&key);
+
+    let ev: FeedbackClosed = w.close(&a, 1).unwrap().event();
+    assert!(!w.svm.exists(&key));
+    assert_eq!(
+        w.svm.lamports(&a),
+        before + feedback_rent - w.svm.lamports(&tombstone_key)
+    );
+
+    let t: FeedbackTombstone = w.svm.get(&tombstone_key);
+    assert_eq!((t.agent, t.client, t.index), (w.agent, a, 1));
+    assert_eq!((t.revoked_at, t.closed_at), (revoked_at, w.svm.now));
+    assert_eq!((ev.index, ev.tombstone), (1, tombstone_key));
+
+    // The index stays used: the next feedback gets index 2.
+    assert_eq!(w.give(&a, Give::score(60)).unwrap(), 2);
+    assert!(w.close(&a, 1).is_err());
+}
+
+#[test]
+fn only_the_client_closes() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(50)).unwrap();
+    w.revoke(&a, 1).unwrap();
+
+    let mallory = w.svm.funded_keypair();
+    let tombstone = pda(&[
+        b"fbt",
+        w.agent.as_ref(),
+        mallory.as_ref(),
+        &1u64.to_le_bytes(),
+    ]);
+    let feedback = w.feedback_key(&a, 1);
+    let err = w
+        .svm
+        .call(
+            erc8004_svm::accounts::CloseFeedbackCtx {
+                feedback,
+                dispute: w.dispute_key(&a, 1),
+                tombstone,
+                client: mallory,
+                system_program: anchor_lang::system_program::ID,
+            },
+            erc8004_svm::instruction::CloseFeedback {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintHasOne)
+    );
+}
+
+#[test]
+fn inline_payload_is_emitted_against_file_hash() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let body = br#"{"latency_ms":120}"#;
+
+    let (index, result) = w.give_tx(&a, Give::score(70).payload(body)).unwrap();
+    let ev = result.event::<FeedbackPayload>();
+    assert_eq!((ev.agent, ev.client, ev.index), (w.agent, a, index));
+    assert_eq!(ev.payload, body);
+    assert_eq!(ev.file_hash, erc8004_svm::util::keccak_bytes(body));
+    assert_eq!(w.feedback(&a, index).file_hash, ev.file_hash);
+
+    let (_, result) = w.give_tx(&a, Give::value(-3, 0).payload(body)).unwrap();
+    assert_eq!(result.event::<FeedbackPayload>().index, 2);
+
+    let (_, result) = w.give_tx(&a, Give::score(70)).unwrap();
+    assert!(result.events::<FeedbackPayload>().is_empty());
+
+    let full = vec![7u8; MAX_INLINE_PAYLOAD];
+    w.give(&a, Give::score(70).payload(&full)).unwrap();
+}
+
+#[test]
+fn inline_payload_must_fit_and_match() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+
+    let big = vec![7u8; MAX_INLINE_PAYLOAD + 1];
+    assert_eq!(
+        w.give(&a, Give::score(70).payload(&big)).unwrap_err(),
+        error(E8004::PayloadTooLarge)
+    );
+
+    let give = Give {
+        file_hash: Some([9; 32]),
+        ..Give::score(70).payload(b"not what was hashed")
+    };
+    assert_eq!(w.give(&a, give).unwrap_err(), error(E8004::PayloadMismatch));
+
+    assert!(!w.svm.exists(&w.feedback_key(&a, 1)));
+}
+
+#[test]
+fn amend_rewrites_feedback_and_moves_summaries() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let (speed, accuracy) = (tag("speed"), tag("accuracy"));
+    w.give(&a, Give::score(20).tags(speed, [0; 32])).unwrap();
+    let old = w.feedback(&a, 1);
+
+    w.svm.warp(60);
+    let amend = Give {
+        tag1_label: Some("accuracy".into()),
+        ..Give::score(70).tags(accuracy, [0; 32])
+    };
+    let ev: FeedbackAmended = w.amend(&a, 1, amend).unwrap().event();
+    let f = w.feedback(&a, 1);
+    assert_eq!((f.score, f.tag1, f.revision), (70, accuracy, 1));
+    assert_eq!((f.file_hash, f.amended_at), ([4; 32], w.svm.now));
+    assert_eq!(f.prev_hash, old.content_hash());
+    assert_eq!((ev.revision, ev.prev_hash), (1, f.prev_hash));
+    assert_eq!(ev.tag1_label.as_deref(), Some("accuracy"));
+
+    let s = w.summary();
+    assert_eq!((s.feedback_count, s.active_count, s.score_sum), (1, 1, 70));
+    let t = w.tag_summary(&speed);
+    assert_eq!((t.active_count, t.score_sum), (0, 0));
+    let t = w.tag_summary(&accuracy);
+    assert_eq!((t.feedback_count, t.active_count, t.score_sum), (1, 1, 70));
+
+    // The chain continues from the amended content.
+    w.amend(&a, 1, Give::score(80).tags(accuracy, [0; 32]))
+        .unwrap();
+    let g = w.feedback(&a, 1);
+    assert_eq!((g.revision, g.prev_hash), (2, f.content_hash()));
+    assert_eq!(w.tag_summary(&accuracy).feedback_count, 1);
+    assert_eq!(w.give(&a, Give::score(1)).unwrap(), 2);
+}
+
+#[test]
+fn amend_only_fits_active_scored_feedback() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::value(5, 0)).unwrap();
+    w.give(&a, Give::score(20)).unwrap();
+
+    assert_eq!(
+        w.amend(&a, 1, Give::score(60)).unwrap_err(),
+        error(E8004::InvalidScore)
+    );
+    assert_eq!(
+        w.amend(&a, 2, Give::score(101)).unwrap_err(),
+        error(E8004::InvalidScore)
+    );
+    w.revoke(&a, 2).unwrap();
+    assert_eq!(
+        w.amend(&a, 2, Give::score(60)).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use common::*;
+use erc8004_svm::validation::{
+    QuorumRequest, ValidationFinalized, ValidationQuorumClosed, ValidationQuorumExpired,
+    ValidationSummary,
+};
+use erc8004_svm::validator::METHOD_REEXECUTION;
+use erc8004_svm::E8004;
+
+fn validators(w: &mut World, n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| w.svm.funded_keypair()).collect()
+}
+
+#[test]
+fn quorum_needs_a_deadline() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 3);
+    let now = w.svm.now;
+    assert_eq!(
+        w.request_quorum(&vs, 2, "ipfs://job", 0).unwrap_err(),
+        error(E8004::RequestExpired)
+    );
+    assert_eq!(
+        w.request_quorum(&vs, 2, "ipfs://job", now).unwrap_err(),
+        error(E8004::RequestExpired)
+    );
+
+    let request = w.request_quorum(&vs, 2, "ipfs://job", now + 100).unwrap();
+    let r: QuorumRequest = w.svm.get(&request);
+    assert_eq!((r.payer, r.deadline), (w.owner, now + 100));
+    w.svm.warp(101);
+    assert_eq!(
+        w.respond_quorum(&vs[0], &request, 50, [0; 32]).unwrap_err(),
+        error(E8004::RequestExpired)
+    );
+}
+
+#[test]
+fn finalized_quorum_closes_after_its_deadline() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 3);
+    let owner = w.owner;
+    let request = w
+        .request_quorum(&vs, 2, "ipfs://job", w.svm.now + 100)
+        .unwrap();
+    w.respond_quorum(&vs[2], &request, 40, [0; 32]).unwrap();
+    let responses = w.quorum_response_metas(&request);
+    assert_eq!(
+        w.close_quorum(&request, &owner, &responses).unwrap_err(),
+        error(E8004::NotFinalized)
+    );
+    let ev: ValidationFinalized = w
+        .respond_quorum(&vs[0], &request, 80, [0; 32])
+        .unwrap()
+        .event();
+    assert_eq!((ev.median, ev.mean), (60, 60));
+    assert_eq!(
+        w.respond_quorum(&vs[1], &request, 10, [0; 32]).unwrap_err(),
+        error(E8004::AlreadyFinalized)
+    );
+
+    let responses = w.quorum_response_metas(&request);
+    assert_eq!(
+        w.close_quorum(&request, &owner, &responses).unwrap_err(),
+        error(E8004::NotFinalized)
+    );
+    w.svm.warp(101);
+    let mallory = w.svm.funded_keypair();
+    assert_eq!(
+        w.close_quorum(&request, &mallory, &responses).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    assert_eq!(
+        w.close_quorum(&request, &owner, &responses[..2])
+            .unwrap_err(),
+        error(E8004::ResponseMissing)
+    );
+    let swapped = [&responses[2..], &responses[..2]].concat();
+    assert_eq!(
+        w.close_quorum(&request, &owner, &swapped).unwrap_err(),
+        error(E8004::ResponseMissing)
+    );
+
+    let before = w.svm.lamports(&vs[0]);
+    let rent = w.svm.lamports(&w.quorum_response_key(&request, &vs[0]));
+    let ev: ValidationQuorumClosed = w
+        .close_quorum(&request, &owner, &responses)
+        .unwrap()
+        .event();
+    assert_eq!(ev.response_count, 2);
+    assert!(!w.svm.exists(&request));
+    assert!(!w.svm.exists(&w.quorum_response_key(&request, &vs[2])));
+    assert_eq!(w.svm.lamports(&vs[0]), before + rent);
+}
+
+#[test]
+fn missed_quorum_expires_with_its_responses() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 3);
+    let owner = w.owner;
+    let before = w.svm.lamports(&owner);
+    let deadline = w.svm.now + 100;
+    let request = w.request_quorum(&vs, 2, "ipfs://job", deadline).unwrap();
+    w.respond_quorum(&vs[1], &request, 40, [0; 32]).unwrap();
+    let responses = w.quorum_response_metas(&request);
+
+    assert_eq!(
+        w.expire_quorum(&request, &owner, &responses).unwrap_err(),
+        error(E8004::NotExpired)
+    );
+    w.svm.warp(101);
+    assert_eq!(
+        w.expire_quorum(&request, &owner, &[]).unwrap_err(),
+        error(E8004::ResponseMissing)
+    );
+    let ev: ValidationQuorumExpired = w
+        .expire_quorum(&request, &owner, &responses)
+        .unwrap()
+        .event();
+    assert_eq!((ev.response_count, ev.deadline), (1, deadline));
+    assert!(!w.svm.exists(&request));
+    assert!(!w.svm.exists(&w.quorum_response_key(&request, &vs[1])));
+    assert_eq!(w.svm.lamports(&owner), before);
+}
+
+#[test]
+fn finalized_quorum_does_not_expire() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 2);
+    let owner = w.owner;
+    let request = w
+        .request_quorum(&vs, 1, "ipfs://job", w.svm.now + 100)
+        .unwrap();
+    w.respond_quorum(&vs[0], &request, 70, [0; 32]).unwrap();
+    w.svm.warp(101);
+    let responses = w.quorum_response_metas(&request);
+    assert_eq!(
+        w.expire_quorum(&request, &owner, &responses).unwrap_err(),
+        error(E8004::AlreadyFinalized)
+    );
+}
+
+#[test]
+fn quorum_validators_meet_the_agent_policy() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 2);
+    w.register_validator(&vs[0], METHOD_REEXECUTION, SOL)
+        .unwrap();
+    w.set_validation_policy(METHOD_REEXECUTION, SOL, None)
+        .unwrap();
+    let deadline = w.svm.now + 100;
+    assert_eq!(
+        w.request_quorum(&vs, 1, "ipfs://job", deadline)
+            .unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+
+    w.register_validator(&vs[1], METHOD_REEXECUTION, SOL)
+        .unwrap();
+    w.request_quorum(&vs, 1, "ipfs://job", deadline).unwrap();
+}
+
+#[test]
+fn quorum_responses_recheck_the_agent_policy() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 2);
+    for v in &vs {
+        w.register_validator(v, METHOD_REEXECUTION, SOL).unwrap();
+    }
+    w.set_validation_policy(METHOD_REEXECUTION, SOL, None)
+        .unwrap();
+    let request = w
+        .request_quorum(&vs, 2, "ipfs://job", w.svm.now + 100)
+        .unwrap();
+
+    // The validator unbonds after the request went out.
+    w.unbond_validator(&vs[0]).unwrap();
+    assert_eq!(
+        w.respond_quorum(&vs[0], &request, 50, [0; 32]).unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+    w.respond_quorum(&vs[1], &request, 50, [0; 32]).unwrap();
+}
+
+#[test]
+fn finalized_quorum_counts_once_in_the_agent_summary() {
+    let mut w = World::new();
+    let vs = validators(&mut w, 3);
+    let request = w
+        .request_quorum(&vs, 3, "ipfs://job", w.svm.now + 100)
+        .unwrap();
+    let all = w.validation_summary_key(None);
+    w.respond_quorum(&vs[0], &request, 20, [0; 32]).unwrap();
+    w.respond_quorum(&vs[1], &request, 90, [0; 32]).unwrap();
+    let s: ValidationSummary = w.svm.get(&all);
+    assert_eq!(s.count, 0, "not counted before it is finalized");
+
+    w.respond_quorum(&vs[2], &request, 40, [0; 32]).unwrap();
+    let s = w.validation_score(&[], [0; 32], &[all]).unwrap();
+    assert_eq!((s.count, s.average_response), (1, 40));
+    let keys: Vec<Pubkey> = vs
+        .iter()
+        .map(|v| w.validation_summary_key(Some(v)))
+        .collect();
+    let s = w.validation_score(&vs, [0; 32], &keys).unwrap();
+    assert_eq!(s.count, 0);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::*;
+use erc8004_svm::reputation::StakeWeightedScore;
+use erc8004_svm::stake::{FeedbackStake, FeedbackUnstaked};
+use erc8004_svm::E8004;
+
+const COOLDOWN: i64 = 7 * 86_400;
+
+fn stake_weighted(w: &mut World) -> StakeWeightedScore {
+    let summary = w.summary_key();
+    w.svm
+        .call(
+            erc8004_svm::accounts::GetStakeWeightedScoreCtx { summary },
+            erc8004_svm::instruction::GetStakeWeightedScore {},
+            &[],
+        )
+        .unwrap()
+        .returned()
+}
+
+#[test]
+fn sol_stake_weights_the_summary_until_unstaked() {
+    let mut w = World::new();
+    w.configure(HALF_LIFE, COOLDOWN).unwrap();
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    w.give(&a, Give::score(90)).unwrap();
+    w.give(&b, Give::score(30)).unwrap();
+
+    let before = w.svm.lamports(&a);
+    w.stake(&a, 1, 3 * SOL).unwrap();
+    w.stake(&b, 1, SOL).unwrap();
+    let escrow = w.stake_key(&a, 1);
+    let rent = w.svm.lamports(&escrow) - 3 * SOL;
+    assert_eq!(w.svm.lamports(&a), before - 3 * SOL - rent);
+    let stake: FeedbackStake = w.svm.get(&escrow);
+    assert_eq!(
+        (stake.amount, stake.mint, stake.staked_at),
+        (3 * SOL, None, w.svm.now)
+    );
+    assert_eq!(w.feedback(&a, 1).stake, 3 * SOL);
+
+    let s = stake_weighted(&mut w);
+    assert_eq!(s.stake_total, 4 * SOL as u128);
+    assert_eq!(s.average_score, 75);
+
+    assert!(w.stake(&a, 1, SOL).is_err(), "already staked");
+    assert_eq!(w.unstake(&a, 1).unwrap_err(), error(E8004::StakeLocked));
+
+    w.svm.warp(COOLDOWN);
+    let ev: FeedbackUnstaked = w.unstake(&a, 1).unwrap().event();
+    assert_eq!(ev.amount, 3 * SOL);
+    assert!(!w.svm.exists(&escrow));
+    assert_eq!(w.svm.lamports(&a), before);
+    assert_eq!(w.feedback(&a, 1).stake, 0);
+
+    let s = stake_weighted(&mut w);
+    assert_eq!((s.stake_total, s.average_score), (SOL as u128, 30));
+}
+
+#[test]
+fn stake_needs_an_amount() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(90)).unwrap();
+    assert_eq!(w.stake(&a, 1, 0).unwrap_err(), error(E8004::InvalidStake));
+}
+
+#[test]
+fn revoked_stake_comes_back_without_summary_weight() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(90)).unwrap();
+    w.stake(&a, 1, SOL).unwrap();
+
+    w.revoke(&a, 1).unwrap();
+    assert_eq!(stake_weighted(&mut w).stake_total, 0);
+
+    // Closing would orphan the escrow, so the stake has to come out first.
+    assert_eq!(w.close(&a, 1).unwrap_err(), error(E8004::StakeLocked));
+    w.unstake(&a, 1).unwrap();
+    assert_eq!(stake_weighted(&mut w).stake_total, 0);
+    w.close(&a, 1).unwrap();
+}
+
+#[test]
+fn cooldown_overflow_is_an_error() {
+    let mut w = World::new();
+    w.configure(HALF_LIFE, i64::MAX).unwrap();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(90)).unwrap();
+    w.stake(&a, 1, SOL).unwrap();
+
+    assert_eq!(w.unstake(&a, 1).unwrap_err(), error(E8004::InvalidConfig));
+}
+
+#[test]
+fn spl_stake_is_escrowed_in_a_vault() {
+    let mut w = World::new();
+    let mint = w.svm.create_mint();
+    w.configure_stake_mint(HALF_LIFE, COOLDOWN, Some(mint))
+        .unwrap();
+    let a = w.svm.funded_keypair();
+    let wallet = w.svm.create_token_account(&mint, &a, 1_000);
+    w.give(&a, Give::score(90)).unwrap();
+
+    assert_eq!(
+        w.stake(&a, 1, 400).unwrap_err(),
+        error(E8004::MissingTokenAccounts)
+    );
+    let other = w.svm.create_mint();
+    let other_wallet = w.svm.create_token_account(&other, &a, 1_000);
+    assert_eq!(
+        w.stake_tokens(&a, 1, 400, Some((other, other_wallet)))
+            .unwrap_err(),
+        error(E8004::MintMismatch)
+    );
+
+    w.stake_tokens(&a, 1, 400, Some((mint, wallet))).unwrap();
+    let vault = pda(&[b"fstake_vault", w.feedback_key(&a, 1).as_ref()]);
+    assert_eq!(w.svm.token_balance(&wallet), 600);
+    assert_eq!(w.svm.token_balance(&vault), 400);
+    let stake: FeedbackStake = w.svm.get(&w.stake_key(&a, 1));
+    assert_eq!((stake.amount, stake.mint), (400, Some(mint)));
+    assert_eq!(stake_weighted(&mut w).stake_total, 400);
+
+    w.svm.warp(COOLDOWN);
+    assert_eq!(
+        w.unstake(&a, 1).unwrap_err(),
+        error(E8004::MissingTokenAccounts)
+    );
+    w.unstake_tokens(&a, 1, Some(wallet)).unwrap();
+    assert_eq!(w.svm.token_balance(&wallet), 1_000);
+    assert!(!w.svm.exists(&vault));
+    assert!(!w.svm.exists(&w.stake_key(&a, 1)));
+    assert_eq!(stake_weighted(&mut w).stake_total, 0);
+}
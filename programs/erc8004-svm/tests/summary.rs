@@ -0,0 +1,297 @@
+mod common;
+
+use common::*;
+use erc8004_svm::reputation::{Feedback, FeedbackSummary};
+use erc8004_svm::util::DECAY_SCALE;
+use erc8004_svm::E8004;
+
+#[test]
+fn give_adds_to_summary() {
+    let mut w = World::new();
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+
+    w.give(&a, Give::score(80)).unwrap();
+    w.give(&a, Give::score(60)).unwrap();
+    w.give(&b, Give::score(100)).unwrap();
+
+    let s = w.summary();
+    assert_eq!(s.agent, w.agent);
+    assert_eq!(s.feedback_count, 3);
+    assert_eq!(s.active_count, 3);
+    assert_eq!(s.revoked_count, 0);
+    assert_eq!(s.score_count, 3);
+    assert_eq!(s.score_sum, 240);
+    assert_eq!(s.score_sq_sum, 80 * 80 + 60 * 60 + 100 * 100);
+    assert_eq!(s.decayed_weight, 3 * DECAY_SCALE);
+    assert_eq!(s.decayed_score_sum, 240 * DECAY_SCALE);
+    assert_eq!(s.last_feedback_at, w.svm.now);
+    assert_eq!(s.client_count, 2);
+    assert!(w.feedback(&a, 1).counted);
+}
+
+#[test]
+fn revoke_takes_feedback_out_of_summary() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(80)).unwrap();
+    w.give(&a, Give::score(60)).unwrap();
+
+    w.revoke(&a, 1).unwrap();
+
+    let s = w.summary();
+    assert_eq!(s.feedback_count, 2);
+    assert_eq!(s.active_count, 1);
+    assert_eq!(s.revoked_count, 1);
+    assert_eq!(s.score_count, 1);
+    assert_eq!(s.score_sum, 60);
+    assert_eq!(s.score_sq_sum, 60 * 60);
+    assert_eq!(s.decayed_weight, DECAY_SCALE);
+    assert_eq!(s.decayed_score_sum, 60 * DECAY_SCALE);
+
+    assert_eq!(
+        w.revoke(&a, 1).unwrap_err(),
+        error(E8004::Unauthorized),
+        "already revoked"
+    );
+}
+
+#[test]
+fn revoke_updates_tag_summaries() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let (speed, accuracy) = (tag("speed"), tag("accuracy"));
+    w.give(&a, Give::score(80).tags(speed, accuracy)).unwrap();
+    w.give(&a, Give::score(40).tags(speed, [0; 32])).unwrap();
+
+    let t = w.tag_summary(&speed);
+    assert_eq!((t.feedback_count, t.active_count, t.score_sum), (2, 2, 120));
+    assert_eq!(w.tag_summary(&accuracy).score_sum, 80);
+
+    w.revoke(&a, 1).unwrap();
+    let t = w.tag_summary(&speed);
+    assert_eq!((t.feedback_count, t.active_count, t.score_sum), (2, 1, 40));
+    let t = w.tag_summary(&accuracy);
+    assert_eq!((t.active_count, t.score_count, t.score_sum), (0, 0, 0));
+}
+
+/// Writes feedback the way it looked before the summaries existed: it was
+/// never added to them, so `counted` reads as false.
+fn put_uncounted(w: &mut World, client: &Pubkey, score: u8) -> Pubkey {
+    let mut feedback = w.feedback(client, 1);
+    feedback.index = 0;
+    feedback.score = score;
+    feedback.counted = false;
+    let key = Pubkey::new_unique();
+    w.svm.put(&key, &feedback, Feedback::SPACE);
+    key
+}
+
+fn revoke_at(
+    w: &mut World,
+    feedback: Pubkey,
+    client: &Pubkey,
+    summary: Option<Pubkey>,
+) -> std::result::Result<TxResult, ProgramError> {
+    w.svm.call(
+        erc8004_svm::accounts::RevokeFeedbackCtx {
+            feedback,
+            summary,
+            config: pda(&[b"rcfg"]),
+            client: *client,
+        },
+        erc8004_svm::instruction::RevokeFeedback { index: 0 },
+        &[],
+    )
+}
+
+#[test]
+fn revoking_uncounted_feedback_leaves_summary_alone() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(80)).unwrap();
+    let old = put_uncounted(&mut w, &a, 10);
+    let before = w.summary();
+
+    let summary = w.summary_key();
+    revoke_at(&mut w, old, &a, Some(summary)).unwrap();
+
+    let after = w.summary();
+    assert_eq!(after.active_count, before.active_count);
+    assert_eq!(after.revoked_count, before.revoked_count);
+    assert_eq!(after.score_sum, 80);
+    assert_eq!(after.decayed_score_sum, before.decayed_score_sum);
+    assert!(w.svm.get::<Feedback>(&old).revoked);
+}
+
+#[test]
+fn uncounted_feedback_revokes_without_summary() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(80)).unwrap();
+    let old = put_uncounted(&mut w, &a, 10);
+
+    revoke_at(&mut w, old, &a, None).unwrap();
+    assert!(w.svm.get::<Feedback>(&old).revoked);
+}
+
+#[test]
+fn counted_feedback_needs_summary() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(80)).unwrap();
+
+    let feedback = w.feedback_key(&a, 1);
+    assert_eq!(
+        revoke_at(&mut w, feedback, &a, None).unwrap_err(),
+        error(E8004::SummaryMissing)
+    );
+    assert!(!w.feedback(&a, 1).revoked);
+}
+
+#[test]
+fn decayed_sums_follow_feedback_age() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(100)).unwrap();
+    w.svm.warp(HALF_LIFE);
+    w.give(&a, Give::score(50)).unwrap();
+
+    // The first feedback is one half-life old when the second arrives.
+    let s = w.summary();
+    assert_eq!(s.decayed_weight, DECAY_SCALE / 2 + DECAY_SCALE);
+    assert_eq!(
+        s.decayed_score_sum,
+        100 * DECAY_SCALE / 2 + 50 * DECAY_SCALE
+    );
+
+    w.svm.warp(HALF_LIFE);
+    w.revoke(&a, 2).unwrap();
+    let s = w.summary();
+    assert_eq!(s.decayed_weight, DECAY_SCALE / 4);
+    assert_eq!(s.decayed_score_sum, 100 * DECAY_SCALE / 4);
+
+    w.revoke(&a, 1).unwrap();
+    let s = w.summary();
+    assert_eq!((s.decayed_weight, s.decayed_score_sum), (0, 0));
+    assert_eq!((s.active_count, s.score_sum, s.score_sq_sum), (0, 0, 0));
+}
+
+fn feedback_summary(
+    w: &mut World,
+    feedback: &[Pubkey],
+    clients: Vec<Pubkey>,
+    tag1: [u8; 32],
+    tag2: [u8; 32],
+) -> std::result::Result<FeedbackSummary, ProgramError> {
+    let remaining: Vec<_> = feedback
+        .iter()
+        .map(|k| AccountMeta::new_readonly(*k, false))
+        .collect();
+    let agent = w.agent;
+    w.svm
+        .call(
+            erc8004_svm::accounts::GetFeedbackSummaryCtx { agent },
+            erc8004_svm::instruction::GetFeedbackSummary {
+                client_addresses: clients,
+                tag1,
+                tag2,
+            },
+            &remaining,
+        )
+        .map(|r| r.returned())
+}
+
+#[test]
+fn feedback_summary_filters_clients_and_tags() {
+    let mut w = World::new();
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let (speed, accuracy) = (tag("speed"), tag("accuracy"));
+    w.give(&a, Give::score(90).tags(speed, accuracy)).unwrap();
+    w.give(&a, Give::score(30).tags(speed, [0; 32])).unwrap();
+    w.give(&b, Give::score(60).tags(accuracy, [0; 32])).unwrap();
+    w.give(&b, Give::score(20).tags(speed, [0; 32])).unwrap();
+    w.revoke(&b, 2).unwrap();
+    let all = [
+        w.feedback_key(&a, 1),
+        w.feedback_key(&a, 2),
+        w.feedback_key(&b, 1),
+        w.feedback_key(&b, 2),
+    ];
+    let zero = [0u8; 32];
+
+    let s = feedback_summary(&mut w, &all, vec![], zero, zero).unwrap();
+    assert_eq!((s.count, s.average_score), (3, 60));
+
+    let s = feedback_summary(&mut w, &all, vec![a], zero, zero).unwrap();
+    assert_eq!((s.count, s.average_score), (2, 60));
+
+    let s = feedback_summary(&mut w, &all, vec![b], zero, zero).unwrap();
+    assert_eq!((s.count, s.average_score), (1, 60));
+
+    let s = feedback_summary(&mut w, &all, vec![], speed, zero).unwrap();
+    assert_eq!((s.count, s.average_score), (2, 60));
+
+    let s = feedback_summary(&mut w, &all, vec![a, b], speed, accuracy).unwrap();
+    assert_eq!((s.count, s.average_score), (1, 90));
+
+    let s = feedback_summary(&mut w, &all, vec![Pubkey::new_unique()], zero, zero).unwrap();
+    assert_eq!((s.count, s.average_score), (0, 0));
+}
+
+#[test]
+fn feedback_summary_rejects_bad_accounts() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(90)).unwrap();
+    let real = w.feedback_key(&a, 1);
+    let zero = [0u8; 32];
+
+    assert_eq!(
+        expect_err(feedback_summary(&mut w, &[real, real], vec![], zero, zero)),
+        error(E8004::DuplicateAccount)
+    );
+
+    // A copy of real feedback at an address that is not its PDA.
+    let copy = put_uncounted(&mut w, &a, 100);
+    assert_eq!(
+        expect_err(feedback_summary(&mut w, &[copy], vec![], zero, zero)),
+        error(E8004::Unauthorized)
+    );
+
+    // Feedback for another agent.
+    let owner = w.svm.funded_keypair();
+    let other = w.register_agent(&owner);
+    let agent = std::mem::replace(&mut w.agent, other);
+    w.give(&a, Give::score(10)).unwrap();
+    let foreign = w.feedback_key(&a, 1);
+    w.agent = agent;
+    assert_eq!(
+        expect_err(feedback_summary(
+            &mut w,
+            &[real, foreign],
+            vec![],
+            zero,
+            zero
+        )),
+        error(E8004::Unauthorized)
+    );
+}
+
+#[test]
+fn feedback_summary_averages_values_apart_from_scores() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    w.give(&a, Give::score(80)).unwrap();
+    w.give(&a, Give::value(3, 0)).unwrap();
+    w.give(&a, Give::value(-25, 1)).unwrap();
+    let all = [
+        w.feedback_key(&a, 1),
+        w.feedback_key(&a, 2),
+        w.feedback_key(&a, 3),
+    ];
+
+    let s = feedback_summary(&mut w, &all, vec![], [0; 32], [0; 32]).unwrap();
+    assert_eq!((s.count, s.average_score), (3, 80));
+    // (3 - 2.5) / 2 in 18 decimals.
+    assert_eq!(s.average_value, 250_000_000_000_000_000);
+}
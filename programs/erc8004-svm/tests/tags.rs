@@ -0,0 +1,203 @@
+mod common;
+
+use common::*;
+use erc8004_svm::reputation::{NewFeedback, TagSummary, MAX_VALUE};
+use erc8004_svm::tags::{TagEntry, TagRegistered};
+use erc8004_svm::E8004;
+
+const E18: i128 = 1_000_000_000_000_000_000;
+
+#[test]
+fn tag_summaries_aggregate_per_tag() {
+    let mut w = World::new();
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let (speed, accuracy) = (tag("speed"), tag("accuracy"));
+
+    w.give(&a, Give::score(90).tags(speed, accuracy)).unwrap();
+    w.give(&b, Give::score(70).tags(speed, speed)).unwrap();
+    w.give(&b, Give::score(10)).unwrap();
+
+    let t = w.tag_summary(&speed);
+    assert_eq!((t.agent, t.tag), (w.agent, speed));
+    // A feedback carrying the same tag twice counts once.
+    assert_eq!((t.feedback_count, t.active_count), (2, 2));
+    assert_eq!((t.score_count, t.score_sum), (2, 160));
+    let t = w.tag_summary(&accuracy);
+    assert_eq!((t.feedback_count, t.score_sum), (1, 90));
+    assert!(!w.svm.exists(&w.tag_summary_key(&[0; 32])));
+
+    let unused = tag("unused");
+    let scores = w.tag_scores(&[speed, accuracy, unused]);
+    assert_eq!(scores.len(), 3);
+    assert_eq!((scores[0].count, scores[0].average_score), (2, 80));
+    assert_eq!((scores[1].count, scores[1].average_score), (1, 90));
+    assert_eq!((scores[2].tag, scores[2].count), (unused, 0));
+}
+
+#[test]
+fn prefunded_tag_summary_is_still_created() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let speed = tag("speed");
+    let key = w.tag_summary_key(&speed);
+    let rent = Rent::default().minimum_balance(TagSummary::SPACE);
+
+    // Enough to block a plain create_account, not enough for rent.
+    w.svm.airdrop(&key, 1);
+    let client_before = w.svm.lamports(&a);
+    w.give(&a, Give::score(50).tags(speed, [0; 32])).unwrap();
+
+    let account = &w.svm.accounts[&key];
+    assert_eq!(account.owner, erc8004_svm::ID);
+    assert_eq!(account.data.len(), TagSummary::SPACE);
+    assert_eq!(account.lamports, rent);
+    assert_eq!(w.tag_summary(&speed).score_sum, 50);
+    assert!(client_before - w.svm.lamports(&a) >= rent - 1);
+}
+
+#[test]
+fn overfunded_tag_summary_needs_no_top_up() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let speed = tag("speed");
+    let key = w.tag_summary_key(&speed);
+
+    w.svm.airdrop(&key, SOL);
+    w.give(&a, Give::score(50).tags(speed, [0; 32])).unwrap();
+
+    assert_eq!(w.svm.lamports(&key), SOL);
+    assert_eq!(w.tag_summary(&speed).active_count, 1);
+}
+
+#[test]
+fn values_aggregate_per_tag_apart_from_scores() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let latency = tag("latency-ms");
+    w.give(&a, Give::value(1_500, 1).tags(latency, [0; 32]))
+        .unwrap();
+    w.give(&a, Give::value(-50, 0).tags(latency, [0; 32]))
+        .unwrap();
+    w.give(&a, Give::score(90).tags(latency, [0; 32])).unwrap();
+    w.give(&a, Give::value(7, 0)).unwrap();
+
+    let t = w.tag_summary(&latency);
+    assert_eq!((t.active_count, t.score_count, t.value_count), (3, 1, 2));
+    assert_eq!(t.score_sum, 90);
+    assert_eq!(t.value_sum, 100 * E18);
+
+    let scores = w.tag_scores(&[latency]);
+    assert_eq!(scores[0].average_score, 90);
+    assert_eq!(scores[0].average_value, 50 * E18);
+
+    // The agent summary only aggregates scores.
+    let s = w.summary();
+    assert_eq!((s.feedback_count, s.active_count), (4, 4));
+    assert_eq!((s.score_count, s.score_sum), (1, 90));
+
+    w.revoke(&a, 1).unwrap();
+    let t = w.tag_summary(&latency);
+    assert_eq!((t.value_count, t.value_sum), (1, -50 * E18));
+}
+
+#[test]
+fn values_are_bounded() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+
+    w.give(&a, Give::value(MAX_VALUE, 18)).unwrap();
+    w.give(&a, Give::value(-1_000_000_000_000, 0)).unwrap();
+    for (value, decimals) in [
+        (MAX_VALUE + 1, 18),
+        (1_000_000_000_001, 0),
+        (i128::MIN, 18),
+        (i128::MAX, 0),
+        (1, 19),
+    ] {
+        assert_eq!(
+            w.give(&a, Give::value(value, decimals)).unwrap_err(),
+            error(E8004::InvalidValue)
+        );
+    }
+}
+
+fn register_tag(
+    w: &mut World,
+    registrar: &Pubkey,
+    tag_hash: [u8; 32],
+    label: &str,
+    description: &str,
+) -> std::result::Result<TxResult, ProgramError> {
+    w.svm.call(
+        erc8004_svm::accounts::RegisterTagCtx {
+            registrar: *registrar,
+            tag_entry: pda(&[b"tag", &tag_hash]),
+            system_program: anchor_lang::system_program::ID,
+        },
+        erc8004_svm::instruction::RegisterTag {
+            tag_hash,
+            tag: label.into(),
+            description: description.into(),
+        },
+        &[],
+    )
+}
+
+#[test]
+fn tags_register_under_their_hash() {
+    let mut w = World::new();
+    let r = w.svm.funded_keypair();
+    let speed = tag("speed");
+
+    let result = register_tag(&mut w, &r, speed, "speed", "Time to answer").unwrap();
+    let entry = w.svm.get::<TagEntry>(&pda(&[b"tag", &speed]));
+    assert_eq!((entry.tag_hash, entry.registrar), (speed, r));
+    assert_eq!(
+        (entry.tag.as_str(), entry.description.as_str()),
+        ("speed", "Time to answer")
+    );
+    assert_eq!(result.event::<TagRegistered>().tag, "speed");
+
+    assert_eq!(
+        register_tag(&mut w, &r, tag("accuracy"), "speed", "").unwrap_err(),
+        error(E8004::TagMismatch)
+    );
+    assert_eq!(
+        register_tag(&mut w, &r, tag(""), "", "").unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    let long = "d".repeat(257);
+    assert_eq!(
+        register_tag(&mut w, &r, tag("long"), "long", &long).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    assert!(register_tag(&mut w, &r, speed, "speed", "again").is_err());
+    assert_eq!(w.svm.get::<TagEntry>(&pda(&[b"tag", &speed])).registrar, r);
+}
+
+#[test]
+fn feedback_events_carry_verified_labels() {
+    let mut w = World::new();
+    let a = w.svm.funded_keypair();
+    let (speed, accuracy) = (tag("speed"), tag("accuracy"));
+
+    let give = Give::score(80)
+        .tags(speed, accuracy)
+        .labels("speed", "accuracy");
+    let (_, result) = w.give_tx(&a, give).unwrap();
+    let ev = result.event::<NewFeedback>();
+    assert_eq!((ev.tag1, ev.tag2), (speed, accuracy));
+    assert_eq!(ev.tag1_label.as_deref(), Some("speed"));
+    assert_eq!(ev.tag2_label.as_deref(), Some("accuracy"));
+
+    let (_, result) = w
+        .give_tx(&a, Give::score(80).tags(speed, accuracy))
+        .unwrap();
+    let ev = result.event::<NewFeedback>();
+    assert_eq!((ev.tag1_label, ev.tag2_label), (None, None));
+
+    let give = Give::score(80)
+        .tags(speed, accuracy)
+        .labels("speed", "speed");
+    assert_eq!(w.give(&a, give).unwrap_err(), error(E8004::TagMismatch));
+}
@@ -0,0 +1,582 @@
+mod common;
+
+use common::*;
+use erc8004_svm::validation::{
+    ValidationClosed, ValidationDeclined, ValidationExpired, ValidationResponse,
+    ValidationResponseEv, ValidationSummary, CLOSE_DELAY, DECLINE_CAPACITY, DECLINE_CONFLICT,
+    DECLINE_UNSUPPORTED, MAX_RESPONSE_URI,
+};
+use erc8004_svm::E8004;
+
+#[test]
+fn responses_are_numbered_and_the_request_tracks_the_latest() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    let r = w.request(&request);
+    assert_eq!((r.agent, r.validator, r.response_count), (w.agent, v, 0));
+    assert_eq!(r.request_hash, w.request_hash(&v, "ipfs://job"));
+
+    let speed = tag("speed");
+    let ev: ValidationResponseEv = w.respond(&v, &request, 40, speed).unwrap().event();
+    assert_eq!((ev.seq, ev.response, ev.tag), (1, 40, speed));
+    w.svm.warp(60);
+    w.respond(&v, &request, 90, speed).unwrap();
+
+    let r = w.request(&request);
+    assert_eq!(r.response_count, 2);
+    assert_eq!(r.latest_response, w.response_key(&request, 2));
+    assert_eq!(r.latest_value, 90);
+
+    let first: ValidationResponse = w.svm.get(&w.response_key(&request, 1));
+    assert_eq!((first.seq, first.response, first.validator), (1, 40, v));
+    let latest: ValidationResponse = w.svm.get(&r.latest_response);
+    assert_eq!((latest.seq, latest.response), (2, 90));
+    assert_eq!(latest.last_update, w.svm.now);
+
+    // The request counts once, with its latest response.
+    for key in [
+        w.validation_summary_key(None),
+        w.validation_summary_key(Some(&v)),
+    ] {
+        let s: ValidationSummary = w.svm.get(&key);
+        assert_eq!((s.count, s.response_sum), (1, 90));
+    }
+}
+
+#[test]
+fn only_the_named_validator_responds() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+
+    let mallory = w.svm.funded_keypair();
+    assert_eq!(
+        w.respond(&mallory, &request, 50, [0; 32]).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    assert_eq!(
+        w.respond(&v, &request, 101, [0; 32]).unwrap_err(),
+        error(E8004::InvalidResponse)
+    );
+    let long = erc8004_svm::instruction::ValidationResponse {
+        response: 50,
+        response_uri: "u".repeat(MAX_RESPONSE_URI + 1),
+        response_hash: [8; 32],
+        tag: [0; 32],
+        tag_label: None,
+        attestation: None,
+    };
+    assert_eq!(
+        w.respond_with(&v, &request, long).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    assert_eq!(w.request(&request).response_count, 0);
+}
+
+#[test]
+fn response_events_carry_verified_labels() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    let labeled = |label: &str| erc8004_svm::instruction::ValidationResponse {
+        response: 50,
+        response_uri: "ipfs://response".into(),
+        response_hash: [8; 32],
+        tag: tag("speed"),
+        tag_label: Some(label.into()),
+        attestation: None,
+    };
+
+    assert_eq!(
+        w.respond_with(&v, &request, labeled("sped")).unwrap_err(),
+        error(E8004::TagMismatch)
+    );
+    let ev: ValidationResponseEv = w
+        .respond_with(&v, &request, labeled("speed"))
+        .unwrap()
+        .event();
+    assert_eq!(ev.tag_label.as_deref(), Some("speed"));
+}
+
+#[test]
+fn request_hash_must_match() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    assert_eq!(
+        w.request_validation(&owner, "ipfs://job", 0, 0)
+            .unwrap_err(),
+        error(E8004::Unauthorized),
+        "the owner cannot validate its own agent"
+    );
+
+    let request = w.request_key(&v, "ipfs://job");
+    let err = w
+        .svm
+        .call(
+            erc8004_svm::accounts::ValidationRequestCtx {
+                agent: w.agent,
+                owner,
+                operator: None,
+                request,
+                policy: pda(&[b"vpolicy", w.agent.as_ref()]),
+                validator_entry: None,
+                bounty_mint: None,
+                owner_token: None,
+                bounty_vault: None,
+                token_program: None,
+                system_program: anchor_lang::system_program::ID,
+            },
+            erc8004_svm::instruction::ValidationRequest {
+                validator: v,
+                request_uri: "ipfs://other".into(),
+                request_hash: w.request_hash(&v, "ipfs://job"),
+                deadline: 0,
+                bounty: 0,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, error(E8004::Unauthorized));
+}
+
+#[test]
+fn deadline_closes_the_request_to_responses() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let now = w.svm.now;
+    assert_eq!(
+        w.request_validation(&v, "ipfs://job", now, 0).unwrap_err(),
+        error(E8004::RequestExpired)
+    );
+
+    let request = w
+        .request_validation(&v, "ipfs://job", now + 100, 0)
+        .unwrap();
+    assert_eq!(w.request(&request).deadline, now + 100);
+    w.svm.warp(100);
+    w.respond(&v, &request, 60, [0; 32]).unwrap();
+    w.svm.warp(1);
+    assert_eq!(
+        w.respond(&v, &request, 70, [0; 32]).unwrap_err(),
+        error(E8004::RequestExpired)
+    );
+    assert_eq!(w.request(&request).latest_value, 60);
+}
+
+#[test]
+fn unanswered_request_expires_with_its_bounty() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let before = w.svm.lamports(&owner);
+    let deadline = w.svm.now + 100;
+    let request = w
+        .request_validation(&v, "ipfs://job", deadline, SOL)
+        .unwrap();
+    assert!(w.svm.lamports(&request) > SOL);
+
+    assert_eq!(
+        w.expire(&request, &owner).unwrap_err(),
+        error(E8004::NotExpired)
+    );
+    w.svm.warp(101);
+    let mallory = w.svm.funded_keypair();
+    assert_eq!(
+        w.expire(&request, &mallory).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+
+    let ev: ValidationExpired = w.expire(&request, &owner).unwrap().event();
+    assert_eq!(
+        (ev.request, ev.deadline, ev.expired_at),
+        (request, deadline, w.svm.now)
+    );
+    assert!(!w.svm.exists(&request));
+    assert_eq!(w.svm.lamports(&owner), before);
+}
+
+#[test]
+fn spl_bounty_is_paid_to_the_first_response() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let mint = w.svm.create_mint();
+    let owner_token = w.svm.create_token_account(&mint, &owner, 1_000);
+    let request = w
+        .request_validation_spl(&v, "ipfs://job", 0, 400, &mint)
+        .unwrap();
+    let vault = w.bounty_vault_key(&request);
+    assert_eq!(w.request(&request).bounty_mint, Some(mint));
+    assert_eq!(w.svm.token_balance(&owner_token), 600);
+    assert_eq!(w.svm.token_balance(&vault), 400);
+
+    assert_eq!(
+        w.respond(&v, &request, 40, [0; 32]).unwrap_err(),
+        error(E8004::MissingTokenAccounts),
+        "no validator token account"
+    );
+    let validator_token = w.svm.create_token_account(&mint, &v, 0);
+    w.respond(&v, &request, 40, [0; 32]).unwrap();
+    assert_eq!(w.svm.token_balance(&validator_token), 400);
+    assert_eq!(
+        (w.request(&request).bounty, w.svm.token_balance(&vault)),
+        (0, 0)
+    );
+
+    // Later revisions are not paid again; closing drops the empty vault.
+    w.respond(&v, &request, 60, [0; 32]).unwrap();
+    assert_eq!(w.svm.token_balance(&validator_token), 400);
+    w.svm.warp(CLOSE_DELAY + 1);
+    w.close_request(&request, &owner).unwrap();
+    assert!(!w.svm.exists(&request) && !w.svm.exists(&vault));
+}
+
+#[test]
+fn unpaid_spl_bounty_is_refunded() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let mint = w.svm.create_mint();
+    let owner_token = w.svm.create_token_account(&mint, &owner, 1_000);
+    let deadline = w.svm.now + 100;
+    let expiring = w
+        .request_validation_spl(&v, "ipfs://expiring", deadline, 300, &mint)
+        .unwrap();
+    let cancelled = w
+        .request_validation_spl(&v, "ipfs://cancelled", 0, 200, &mint)
+        .unwrap();
+    assert_eq!(w.svm.token_balance(&owner_token), 500);
+
+    let before = w.svm.lamports(&owner);
+    let vault = w.bounty_vault_key(&cancelled);
+    let rent = w.svm.lamports(&cancelled) + w.svm.lamports(&vault);
+    w.cancel(&cancelled, &owner).unwrap();
+    assert_eq!(w.svm.token_balance(&owner_token), 700);
+    assert!(!w.svm.exists(&vault));
+    assert_eq!(w.svm.lamports(&owner), before + rent);
+
+    w.svm.warp(101);
+    w.expire(&expiring, &owner).unwrap();
+    assert_eq!(w.svm.token_balance(&owner_token), 1_000);
+    assert!(!w.svm.exists(&w.bounty_vault_key(&expiring)));
+}
+
+#[test]
+fn mint_without_a_bounty_leaves_no_vault_behind() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let mint = w.svm.create_mint();
+    let request = w
+        .request_validation_spl(&v, "ipfs://job", 0, 0, &mint)
+        .unwrap();
+    let r = w.request(&request);
+    assert_eq!((r.bounty_mint, r.bounty), (None, 0));
+    assert!(!w.svm.exists(&w.bounty_vault_key(&request)));
+
+    w.cancel(&request, &owner).unwrap();
+    assert!(!w.svm.exists(&request));
+}
+
+#[test]
+fn only_unanswered_requests_with_a_deadline_expire() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let open = w.request_validation(&v, "ipfs://open", 0, 0).unwrap();
+    let deadline = w.svm.now + 100;
+    let answered = w
+        .request_validation(&v, "ipfs://answered", deadline, 0)
+        .unwrap();
+    w.respond(&v, &answered, 50, [0; 32]).unwrap();
+
+    w.svm.warp(101);
+    assert_eq!(
+        w.expire(&open, &owner).unwrap_err(),
+        error(E8004::NotExpired)
+    );
+    assert_eq!(
+        w.expire(&answered, &owner).unwrap_err(),
+        error(E8004::NotExpired)
+    );
+    assert!(w.svm.exists(&open) && w.svm.exists(&answered));
+}
+
+#[test]
+fn open_request_closes_a_while_after_its_latest_response() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 40, [0; 32]).unwrap();
+    assert_eq!(
+        w.close_request(&request, &owner).unwrap_err(),
+        error(E8004::NotFinalized)
+    );
+
+    // A revision restarts the delay.
+    w.svm.warp(CLOSE_DELAY);
+    w.respond(&v, &request, 80, [0; 32]).unwrap();
+    w.svm.warp(CLOSE_DELAY);
+    assert_eq!(
+        w.close_request(&request, &owner).unwrap_err(),
+        error(E8004::NotFinalized)
+    );
+
+    w.svm.warp(1);
+    let validator_before = w.svm.lamports(&v);
+    let responses =
+        w.svm.lamports(&w.response_key(&request, 1)) + w.svm.lamports(&w.response_key(&request, 2));
+    let ev: ValidationClosed = w.close_request(&request, &owner).unwrap().event();
+    assert_eq!(ev.response_count, 2);
+    assert!(!w.svm.exists(&request));
+    assert!(!w.svm.exists(&w.response_key(&request, 1)));
+    assert_eq!(w.svm.lamports(&v), validator_before + responses);
+}
+
+#[test]
+fn request_with_a_deadline_closes_after_it() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    let deadline = w.svm.now + 100;
+    let request = w.request_validation(&v, "ipfs://job", deadline, 0).unwrap();
+    assert_eq!(
+        w.close_request(&request, &owner).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram),
+        "no response yet"
+    );
+    w.respond(&v, &request, 40, [0; 32]).unwrap();
+    assert_eq!(
+        w.close_request(&request, &owner).unwrap_err(),
+        error(E8004::NotFinalized)
+    );
+    w.svm.warp(101);
+    w.close_request(&request, &owner).unwrap();
+}
+
+#[test]
+fn summary_reads_the_summary_accounts() {
+    let mut w = World::new();
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let first = w.request_validation(&a, "ipfs://job", 0, 0).unwrap();
+    let second = w.request_validation(&b, "ipfs://job", 0, 0).unwrap();
+    w.respond(&a, &first, 20, [0; 32]).unwrap();
+    w.respond(&a, &first, 40, [0; 32]).unwrap();
+    w.respond(&b, &second, 80, [0; 32]).unwrap();
+
+    let all = w.validation_summary_key(None);
+    let s = w.validation_score(&[], [0; 32], &[all]).unwrap();
+    assert_eq!((s.count, s.average_response), (2, 60));
+    let keys = [
+        w.validation_summary_key(Some(&a)),
+        w.validation_summary_key(Some(&b)),
+    ];
+    let s = w.validation_score(&[a], [0; 32], &keys).unwrap();
+    assert_eq!((s.count, s.average_response), (1, 40));
+
+    let unknown = w.svm.funded_keypair();
+    let s = w
+        .validation_score(
+            &[unknown],
+            [0; 32],
+            &[w.validation_summary_key(Some(&unknown))],
+        )
+        .unwrap();
+    assert_eq!(s.count, 0);
+    assert_eq!(
+        expect_err(w.validation_score(&[b], [0; 32], &[keys[0]])),
+        error(E8004::ValidationSummaryMissing)
+    );
+    assert_eq!(
+        expect_err(w.validation_score(&[a, a], [0; 32], &keys)),
+        error(E8004::DuplicateAccount)
+    );
+}
+
+#[test]
+fn summary_filters_requests_by_latest_tag() {
+    let mut w = World::new();
+    let (a, b) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let (speed, safety) = (tag("speed"), tag("safety"));
+    let first = w.request_validation(&a, "ipfs://first", 0, 0).unwrap();
+    let second = w.request_validation(&b, "ipfs://second", 0, 0).unwrap();
+    let unanswered = w.request_validation(&a, "ipfs://third", 0, 0).unwrap();
+    w.respond(&a, &first, 20, speed).unwrap();
+    w.respond(&b, &second, 70, speed).unwrap();
+    let accounts = [first, second, unanswered];
+
+    let s = w.validation_score(&[], speed, &accounts).unwrap();
+    assert_eq!((s.count, s.average_response), (2, 45));
+    let s = w.validation_score(&[b], speed, &accounts).unwrap();
+    assert_eq!((s.count, s.average_response), (1, 70));
+    let s = w.validation_score(&[], safety, &accounts).unwrap();
+    assert_eq!(s.count, 0);
+
+    // A revision under another tag moves the request out of the first tag.
+    w.respond(&a, &first, 50, safety).unwrap();
+    let s = w.validation_score(&[], speed, &accounts).unwrap();
+    assert_eq!((s.count, s.average_response), (1, 70));
+    let s = w.validation_score(&[], safety, &accounts).unwrap();
+    assert_eq!((s.count, s.average_response), (1, 50));
+
+    // Accounts may not be counted twice or belong to another agent, and
+    // earlier responses and quorum responses are not requests.
+    assert_eq!(
+        expect_err(w.validation_score(&[], speed, &[second, second])),
+        error(E8004::DuplicateAccount)
+    );
+    let other = w.response_key(&first, 1);
+    assert_eq!(
+        expect_err(w.validation_score(&[], speed, &[other])),
+        error(E8004::RequestNotFound)
+    );
+    let quorum = w
+        .request_quorum(&[a, b], 1, "ipfs://quorum", w.svm.now + 100)
+        .unwrap();
+    w.respond_quorum(&b, &quorum, 90, speed).unwrap();
+    let quorum_response = w.quorum_response_key(&quorum, &b);
+    assert_eq!(
+        expect_err(w.validation_score(&[], speed, &[quorum_response])),
+        error(E8004::RequestNotFound)
+    );
+    let owner = w.svm.funded_keypair();
+    w.agent = w.register_agent(&owner);
+    assert_eq!(
+        expect_err(w.validation_score(&[], speed, &[second])),
+        error(E8004::RequestNotFound)
+    );
+}
+
+#[test]
+fn operator_request_refunds_its_payer() {
+    let mut w = World::new();
+    let (v, operator) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let owner = w.owner;
+    w.set_operator(&operator).unwrap();
+    let before = w.svm.lamports(&operator);
+    let owner_before = w.svm.lamports(&owner);
+
+    let request = w
+        .request_validation_as(&operator, &v, "ipfs://job", 0, SOL)
+        .unwrap();
+    assert_eq!(w.request(&request).payer, operator);
+    let mallory = w.svm.funded_keypair();
+    assert_eq!(
+        w.cancel(&request, &mallory).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    w.cancel(&request, &operator).unwrap();
+    assert_eq!(w.svm.lamports(&operator), before);
+
+    // The owner can withdraw it too, but the refund still goes to the
+    // operator that paid.
+    let request = w
+        .request_validation_as(&operator, &v, "ipfs://job", 0, SOL)
+        .unwrap();
+    w.cancel(&request, &owner).unwrap();
+    assert_eq!(w.svm.lamports(&operator), before);
+    assert_eq!(w.svm.lamports(&owner), owner_before);
+}
+
+#[test]
+fn operator_request_expires_and_closes_to_its_payer() {
+    let mut w = World::new();
+    let (v, operator) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    w.set_operator(&operator).unwrap();
+    let before = w.svm.lamports(&operator);
+    let deadline = w.svm.now + 100;
+    let unanswered = w
+        .request_validation_as(&operator, &v, "ipfs://first", deadline, SOL)
+        .unwrap();
+    let answered = w
+        .request_validation_as(&operator, &v, "ipfs://second", deadline, 0)
+        .unwrap();
+    let rent = w.svm.lamports(&answered);
+    w.respond(&v, &answered, 50, [0; 32]).unwrap();
+    w.svm.warp(101);
+
+    w.expire(&unanswered, &operator).unwrap();
+    assert_eq!(w.svm.lamports(&operator), before - rent);
+    let owner = w.owner;
+    w.close_request(&answered, &owner).unwrap();
+    assert_eq!(w.svm.lamports(&operator), before);
+}
+
+#[test]
+fn request_without_a_payer_refunds_the_agent_owner() {
+    let mut w = World::new();
+    let (v, operator) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let owner = w.owner;
+    w.set_operator(&operator).unwrap();
+    let request = w
+        .request_validation_as(&operator, &v, "ipfs://job", 0, 0)
+        .unwrap();
+
+    // As left by versions that did not record the payer.
+    let mut r = w.request(&request);
+    r.payer = Pubkey::default();
+    let space = w.svm.accounts[&request].data.len();
+    w.svm.put(&request, &r, space);
+
+    let (owner_before, rent) = (w.svm.lamports(&owner), w.svm.lamports(&request));
+    assert_eq!(
+        w.cancel(&request, &operator).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    w.cancel(&request, &owner).unwrap();
+    assert_eq!(w.svm.lamports(&owner), owner_before + rent);
+}
+
+#[test]
+fn declined_request_takes_no_responses() {
+    let mut w = World::new();
+    let (v, other) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    let owner = w.owner;
+    let request = w.request_validation(&v, "ipfs://job", 0, SOL).unwrap();
+
+    assert_eq!(
+        w.decline(&other, &request, DECLINE_CAPACITY).unwrap_err(),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintHasOne)
+    );
+    let ev: ValidationDeclined = w.decline(&v, &request, DECLINE_CAPACITY).unwrap().event();
+    assert_eq!(
+        (ev.reason, ev.reason_uri.as_str()),
+        (DECLINE_CAPACITY, "ipfs://reason")
+    );
+    let r = w.request(&request);
+    assert!(r.declined);
+    assert_eq!((r.decline_reason, r.bounty), (DECLINE_CAPACITY, SOL));
+
+    assert_eq!(
+        w.decline(&v, &request, DECLINE_CONFLICT).unwrap_err(),
+        error(E8004::RequestDeclined)
+    );
+    assert_eq!(
+        w.respond(&v, &request, 50, [0; 32]).unwrap_err(),
+        error(E8004::RequestDeclined)
+    );
+
+    // Cancelling refunds the bounty and frees the hash for another try.
+    let before = w.svm.lamports(&owner);
+    let held = w.svm.lamports(&request);
+    w.cancel(&request, &owner).unwrap();
+    assert_eq!(w.svm.lamports(&owner), before + held);
+    w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 50, [0; 32]).unwrap();
+}
+
+#[test]
+fn answered_request_cannot_be_declined() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 50, [0; 32]).unwrap();
+    assert_eq!(
+        w.decline(&v, &request, DECLINE_UNSUPPORTED).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    assert!(!w.request(&request).declined);
+}
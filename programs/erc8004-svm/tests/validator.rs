@@ -0,0 +1,277 @@
+mod common;
+
+use common::*;
+use erc8004_svm::validation::{QuorumResponse, ValidationResponse, CLOSE_DELAY};
+use erc8004_svm::validator::{
+    ResponseChallenged, ValidatorEntry, ValidatorSlashed, MAX_SLASH_BPS, METHOD_REEXECUTION,
+    METHOD_TEE,
+};
+use erc8004_svm::E8004;
+
+#[test]
+fn slash_needs_a_response_by_the_validator() {
+    let mut w = World::new();
+    let (v, other) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    w.register_validator(&v, METHOD_REEXECUTION, 10 * SOL)
+        .unwrap();
+    w.register_validator(&other, METHOD_REEXECUTION, SOL)
+        .unwrap();
+    let request = w.request_validation(&other, "ipfs://job", 0, 0).unwrap();
+    w.respond(&other, &request, 90, [0; 32]).unwrap();
+    let others = w.response_key(&request, 1);
+
+    assert_eq!(
+        w.slash_validator(&v, &others, SOL).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 90, [0; 32]).unwrap();
+    let response = w.response_key(&request, 1);
+    let treasury = w.svm.lamports(&w.authority);
+    let ev: ValidatorSlashed = w.slash_validator(&v, &response, 2 * SOL).unwrap().event();
+    assert_eq!(
+        (ev.response, ev.amount, ev.remaining),
+        (response, 2 * SOL, 8 * SOL)
+    );
+    assert_eq!(w.svm.lamports(&w.authority), treasury + 2 * SOL);
+    let slashed: ValidationResponse = w.svm.get(&response);
+    assert!(slashed.slashed);
+
+    assert_eq!(
+        w.slash_validator(&v, &response, SOL).unwrap_err(),
+        error(E8004::AlreadySlashed)
+    );
+}
+
+#[test]
+fn slash_is_capped() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    w.register_validator(&v, METHOD_REEXECUTION, 10 * SOL)
+        .unwrap();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 90, [0; 32]).unwrap();
+    let response = w.response_key(&request, 1);
+
+    let cap = 10 * SOL * MAX_SLASH_BPS / 10_000;
+    assert_eq!(
+        w.slash_validator(&v, &response, cap + 1).unwrap_err(),
+        error(E8004::InvalidStake)
+    );
+    assert_eq!(
+        w.slash_validator(&v, &response, 0).unwrap_err(),
+        error(E8004::InvalidStake)
+    );
+    w.slash_validator(&v, &response, cap).unwrap();
+    let entry: ValidatorEntry = w.svm.get(&w.validator_entry(&v).unwrap());
+    assert_eq!(entry.stake, 10 * SOL - cap);
+}
+
+#[test]
+fn response_rechecks_the_agent_policy() {
+    let mut w = World::new();
+    let (v, unregistered) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    w.register_validator(&v, METHOD_REEXECUTION, SOL).unwrap();
+    w.set_validation_policy(METHOD_REEXECUTION, SOL, None)
+        .unwrap();
+    assert_eq!(
+        w.request_validation(&unregistered, "ipfs://job", 0, 0)
+            .unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+    let first = w.request_validation(&v, "ipfs://first", 0, 0).unwrap();
+    let second = w.request_validation(&v, "ipfs://second", 0, 0).unwrap();
+    w.respond(&v, &first, 80, [0; 32]).unwrap();
+
+    // The agent tightens its policy after the requests went out.
+    w.set_validation_policy(METHOD_REEXECUTION | METHOD_TEE, SOL, None)
+        .unwrap();
+    assert_eq!(
+        w.respond(&v, &second, 80, [0; 32]).unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+    w.set_validation_policy(METHOD_REEXECUTION, SOL, None)
+        .unwrap();
+    w.respond(&v, &second, 80, [0; 32]).unwrap();
+
+    // An unbonding validator no longer satisfies it.
+    w.unbond_validator(&v).unwrap();
+    assert_eq!(
+        w.respond(&v, &first, 60, [0; 32]).unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+}
+
+#[test]
+fn policy_counts_stake_in_its_mint_only() {
+    let mut w = World::new();
+    let mint = w.svm.create_mint();
+    w.set_validator_config(Some(mint), 0, 0).unwrap();
+    let v = w.svm.funded_keypair();
+    w.svm.create_token_account(&mint, &v, 10);
+    w.register_validator(&v, METHOD_REEXECUTION, 5).unwrap();
+
+    // Five tokens are not five lamports.
+    w.set_validation_policy(METHOD_REEXECUTION, 5, None)
+        .unwrap();
+    assert_eq!(
+        w.request_validation(&v, "ipfs://job", 0, 0).unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+    w.set_validation_policy(METHOD_REEXECUTION, 6, Some(mint))
+        .unwrap();
+    assert_eq!(
+        w.request_validation(&v, "ipfs://job", 0, 0).unwrap_err(),
+        error(E8004::ValidatorNotRegistered)
+    );
+    w.set_validation_policy(METHOD_REEXECUTION, 5, Some(mint))
+        .unwrap();
+    w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+
+    // Without a minimum any stake will do.
+    w.set_validation_policy(METHOD_REEXECUTION, 0, None)
+        .unwrap();
+    w.request_validation(&v, "ipfs://other", 0, 0).unwrap();
+}
+
+#[test]
+fn spl_stake_is_escrowed_slashed_and_withdrawn() {
+    let mut w = World::new();
+    let mint = w.svm.create_mint();
+    w.set_validator_config(Some(mint), 0, 0).unwrap();
+    let v = w.svm.funded_keypair();
+    assert_eq!(
+        w.register_validator(&v, METHOD_REEXECUTION, 1_000)
+            .unwrap_err(),
+        error(E8004::MissingTokenAccounts)
+    );
+    let validator_token = w.svm.create_token_account(&mint, &v, 1_500);
+    w.register_validator(&v, METHOD_REEXECUTION, 1_000).unwrap();
+    let vault = pda(&[b"validator_vault", v.as_ref()]);
+    assert_eq!(w.svm.token_balance(&vault), 1_000);
+    assert_eq!(w.svm.token_balance(&validator_token), 500);
+
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 90, [0; 32]).unwrap();
+    let response = w.response_key(&request, 1);
+    assert_eq!(
+        w.slash_validator(&v, &response, 300).unwrap_err(),
+        error(E8004::MissingTokenAccounts),
+        "no treasury token account"
+    );
+    let authority = w.authority;
+    let treasury = w.svm.create_token_account(&mint, &authority, 0);
+    let ev: ValidatorSlashed = w.slash_validator(&v, &response, 300).unwrap().event();
+    assert_eq!((ev.amount, ev.remaining), (300, 700));
+    assert_eq!(w.svm.token_balance(&treasury), 300);
+    assert_eq!(w.svm.token_balance(&vault), 700);
+
+    w.unbond_validator(&v).unwrap();
+    w.withdraw_validator(&v).unwrap();
+    assert_eq!(w.svm.token_balance(&validator_token), 1_200);
+    assert!(!w.svm.exists(&vault));
+    assert_eq!(w.validator_entry(&v), None);
+}
+
+#[test]
+fn quorum_response_is_slashable() {
+    let mut w = World::new();
+    let (v, other) = (w.svm.funded_keypair(), w.svm.funded_keypair());
+    w.register_validator(&v, METHOD_REEXECUTION, 10 * SOL)
+        .unwrap();
+    let request = w
+        .request_quorum(&[v, other], 2, "ipfs://job", w.svm.now + 100)
+        .unwrap();
+    w.respond_quorum(&v, &request, 90, [0; 32]).unwrap();
+    w.respond_quorum(&other, &request, 10, [0; 32]).unwrap();
+    let (response, others) = (
+        w.quorum_response_key(&request, &v),
+        w.quorum_response_key(&request, &other),
+    );
+
+    assert_eq!(
+        w.slash_validator(&v, &others, SOL).unwrap_err(),
+        error(E8004::Unauthorized)
+    );
+    let ev: ValidatorSlashed = w.slash_validator(&v, &response, SOL).unwrap().event();
+    assert_eq!((ev.response, ev.remaining), (response, 9 * SOL));
+    let slashed: QuorumResponse = w.svm.get(&response);
+    assert!(slashed.slashed);
+    assert_eq!(
+        w.slash_validator(&v, &response, SOL).unwrap_err(),
+        error(E8004::AlreadySlashed)
+    );
+}
+
+#[test]
+fn challenged_response_outlives_its_request() {
+    let mut w = World::new();
+    let v = w.svm.funded_keypair();
+    let owner = w.owner;
+    w.register_validator(&v, METHOD_REEXECUTION, 10 * SOL)
+        .unwrap();
+    let request = w.request_validation(&v, "ipfs://job", 0, 0).unwrap();
+    w.respond(&v, &request, 90, [0; 32]).unwrap();
+    w.respond(&v, &request, 95, [0; 32]).unwrap();
+    let first = w.response_key(&request, 1);
+
+    let ev: ResponseChallenged = w.challenge(&first, true).unwrap().event();
+    assert_eq!((ev.validator, ev.response, ev.challenged), (v, first, true));
+    w.svm.warp(CLOSE_DELAY + 1);
+    assert_eq!(
+        w.close_request(&request, &owner).unwrap_err(),
+        error(E8004::ResponseChallenged)
+    );
+
+    // A dismissed challenge frees the request again.
+    w.challenge(&first, false).unwrap();
+    let latest = w.response_key(&request, 2);
+    w.challenge(&latest, true).unwrap();
+    assert_eq!(
+        w.close_request(&request, &owner).unwrap_err(),
+        error(E8004::ResponseChallenged)
+    );
+
+    // So does a slash, after which the response cannot be challenged again.
+    w.slash_validator(&v, &latest, SOL).unwrap();
+    let response: ValidationResponse = w.svm.get(&latest);
+    assert!(response.slashed && !response.challenged);
+    assert_eq!(
+        w.challenge(&latest, true).unwrap_err(),
+        error(E8004::AlreadySlashed)
+    );
+    w.close_request(&request, &owner).unwrap();
+    assert!(!w.svm.exists(&latest));
+}
+
+#[test]
+fn challenged_quorum_response_blocks_closing() {
+    let mut w = World::new();
+    let vs = [w.svm.funded_keypair(), w.svm.funded_keypair()];
+    let owner = w.owner;
+    let finalized = w
+        .request_quorum(&vs, 1, "ipfs://finalized", w.svm.now + 100)
+        .unwrap();
+    let missed = w
+        .request_quorum(&vs, 2, "ipfs://missed", w.svm.now + 100)
+        .unwrap();
+    w.respond_quorum(&vs[0], &finalized, 50, [0; 32]).unwrap();
+    w.respond_quorum(&vs[1], &missed, 50, [0; 32]).unwrap();
+    w.challenge(&w.quorum_response_key(&finalized, &vs[0]), true)
+        .unwrap();
+    w.challenge(&w.quorum_response_key(&missed, &vs[1]), true)
+        .unwrap();
+
+    w.svm.warp(101);
+    let responses = w.quorum_response_metas(&finalized);
+    assert_eq!(
+        w.close_quorum(&finalized, &owner, &responses).unwrap_err(),
+        error(E8004::ResponseChallenged)
+    );
+    let responses = w.quorum_response_metas(&missed);
+    assert_eq!(
+        w.expire_quorum(&missed, &owner, &responses).unwrap_err(),
+        error(E8004::ResponseChallenged)
+    );
+}
@@ -1,5 +1,6 @@
 import { AnchorProvider, Program, Idl, BN } from "@coral-xyz/anchor";
 import {
+  AccountMeta,
   Connection,
   PublicKey,
   SystemProgram,
@@ -7,9 +8,22 @@ import {
   TransactionInstruction,
   SYSVAR_INSTRUCTIONS_PUBKEY,
 } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import keccak from "keccak";
 import idl from "./idl.json" assert { type: "json" };
-import { PROGRAM_ID, seeds, FeedbackAuth, encodeFeedbackAuth } from "./types";
+import {
+  PROGRAM_ID,
+  CLIENTS_PER_PAGE,
+  seeds,
+  FeedbackAuth,
+  encodeFeedbackAuth,
+  AttestationReport,
+  encodeAttestationReport,
+} from "./types";
 
 export function createClient(
   connection: Connection,
@@ -75,6 +89,22 @@ export function createClient(
         .rpc();
       return { meta };
     },
+    async registerTag(tag: string, description: string) {
+      const tagHash = keccak("keccak256").update(tag).digest();
+      const [tagEntry] = PublicKey.findProgramAddressSync(
+        seeds.tag(tagHash),
+        PROGRAM_ID
+      );
+      await program.methods
+        .registerTag(Array.from(tagHash), tag, description)
+        .accounts({
+          registrar: wallet.publicKey,
+          tagEntry,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      return { tagEntry, tagHash: Uint8Array.from(tagHash) };
+    },
     async giveFeedbackEd25519(params: {
       agent: PublicKey;
       score: number;
@@ -84,6 +114,9 @@ export function createClient(
       fileHash: Uint8Array;
       auth: FeedbackAuth;
       signature: Uint8Array;
+      tag1Label?: string;
+      tag2Label?: string;
+      payload?: Uint8Array;
     }) {
       const { agent, score, tag1, tag2, fileUri, fileHash, auth, signature } =
         params;
@@ -125,6 +158,30 @@ export function createClient(
         PROGRAM_ID
       );
 
+      const [summary] = PublicKey.findProgramAddressSync(
+        seeds.rsum(agent),
+        PROGRAM_ID
+      );
+
+      const [config] = PublicKey.findProgramAddressSync(
+        seeds.rcfg(),
+        PROGRAM_ID
+      );
+      const remaining = tagSummaryAccounts(agent, tag1, tag2);
+      if (!idxAccount) {
+        // First feedback from this client: it is appended to the client list.
+        const summaryAccount =
+          await program.account.reputationSummary.fetchNullable(summary);
+        const clientCount = summaryAccount
+          ? Number((summaryAccount.clientCount as BN).toString())
+          : 0;
+        const [clientPage] = PublicKey.findProgramAddressSync(
+          seeds.clients(agent, Math.floor(clientCount / CLIENTS_PER_PAGE)),
+          PROGRAM_ID
+        );
+        remaining.push({ pubkey: clientPage, isSigner: false, isWritable: true });
+      }
+
       const authStructBytes = encodeFeedbackAuth(auth);
       const digest = keccak("keccak256").update(authStructBytes).digest();
       const edIx = buildEd25519Ix(
@@ -142,24 +199,188 @@ export function createClient(
           Array.from(fileHash),
           Array.from(authStructBytes),
           Array.from(signature),
-          new BN(nextIndex.toString())
+          new BN(nextIndex.toString()),
+          params.tag1Label ?? null,
+          params.tag2Label ?? null,
+          params.payload ? Buffer.from(params.payload) : null
         )
         .accounts({
           agent,
           client: wallet.publicKey,
           idx,
           feedback,
+          summary,
+          config,
           ixSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(remaining)
         .preInstructions([edIx])
         .rpc();
 
       return { feedback, index: nextIndex };
     },
+    async validationResponse(params: {
+      request: PublicKey;
+      response: number;
+      responseUri: string;
+      responseHash: Uint8Array;
+      tag: Uint8Array;
+      tagLabel?: string;
+      attestation?: {
+        key: PublicKey;
+        report: AttestationReport;
+        signature: Uint8Array;
+      };
+    }) {
+      const { request, response, responseUri, responseHash, tag, attestation } =
+        params;
+
+      if (responseHash.length !== 32 || tag.length !== 32) {
+        throw new Error("responseHash and tag must be 32 bytes");
+      }
+      if (response < 0 || response > 100) {
+        throw new Error("response must be in 0..=100");
+      }
+
+      const requestAccount =
+        await program.account.validationRequest.fetch(request);
+      const agent = requestAccount.agent as PublicKey;
+      const seq = (requestAccount.responseCount as number) + 1;
+      const [responseAcc] = PublicKey.findProgramAddressSync(
+        seeds.vres(request, seq),
+        PROGRAM_ID
+      );
+      const [agentSummary] = PublicKey.findProgramAddressSync(
+        seeds.vsum(agent),
+        PROGRAM_ID
+      );
+      const [validatorSummary] = PublicKey.findProgramAddressSync(
+        seeds.vsum(agent, wallet.publicKey),
+        PROGRAM_ID
+      );
+      const [policy] = PublicKey.findProgramAddressSync(
+        seeds.vpolicy(agent),
+        PROGRAM_ID
+      );
+      const [entry] = PublicKey.findProgramAddressSync(
+        seeds.validator(wallet.publicKey),
+        PROGRAM_ID
+      );
+      const validatorEntry = (await connection.getAccountInfo(entry))
+        ? entry
+        : null;
+
+      let attestationArg = null;
+      let attestationKey: PublicKey | null = null;
+      const preInstructions: TransactionInstruction[] = [];
+
+      // An unpaid SPL bounty goes from the request's vault to the
+      // validator's associated token account, created if missing.
+      const bountyMint = requestAccount.bountyMint as PublicKey | null;
+      let bountyVault: PublicKey | null = null;
+      let validatorToken: PublicKey | null = null;
+      if (bountyMint && !(requestAccount.bounty as BN).isZero()) {
+        [bountyVault] = PublicKey.findProgramAddressSync(
+          seeds.vvault(request),
+          PROGRAM_ID
+        );
+        validatorToken = getAssociatedTokenAddressSync(
+          bountyMint,
+          wallet.publicKey
+        );
+        preInstructions.push(
+          createAssociatedTokenAccountIdempotentInstruction(
+            wallet.publicKey,
+            validatorToken,
+            wallet.publicKey,
+            bountyMint
+          )
+        );
+      }
+      if (attestation) {
+        if (attestation.signature.length !== 64) {
+          throw new Error("ed25519 signature must be 64 bytes");
+        }
+        // Attested responses need the validator registered with the TEE method.
+        if (!validatorEntry) {
+          throw new Error("attesting validators must be registered");
+        }
+        const { report } = attestation;
+        [attestationKey] = PublicKey.findProgramAddressSync(
+          seeds.attkey(attestation.key),
+          PROGRAM_ID
+        );
+        const digest = keccak("keccak256")
+          .update(encodeAttestationReport(report))
+          .digest();
+        preInstructions.push(
+          buildEd25519Ix(
+            Uint8Array.from(digest),
+            attestation.key,
+            attestation.signature
+          )
+        );
+        attestationArg = {
+          report: {
+            kind: report.kind,
+            measurement: Array.from(report.measurement),
+            reportData: Array.from(report.reportData),
+            timestamp: new BN(report.timestamp.toString()),
+          },
+          signature: Buffer.from(attestation.signature),
+        };
+      }
+
+      await program.methods
+        .validationResponse(
+          response,
+          responseUri,
+          Array.from(responseHash),
+          Array.from(tag),
+          params.tagLabel ?? null,
+          attestationArg
+        )
+        .accounts({
+          request,
+          validator: wallet.publicKey,
+          responseAcc,
+          agentSummary,
+          validatorSummary,
+          policy,
+          validatorEntry,
+          attestationKey,
+          ixSysvar: attestation ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
+          validatorToken,
+          bountyVault,
+          tokenProgram: bountyVault ? TOKEN_PROGRAM_ID : null,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions(preInstructions)
+        .rpc();
+
+      return { response: responseAcc, seq };
+    },
   };
 }
 
+export function tagSummaryAccounts(
+  agent: PublicKey,
+  tag1: Uint8Array,
+  tag2: Uint8Array
+): AccountMeta[] {
+  const tags = [tag1, tag2].filter(
+    (tag, i, all) =>
+      tag.some((b) => b !== 0) &&
+      all.findIndex((t) => Buffer.from(t).equals(Buffer.from(tag))) === i
+  );
+  return tags.map((tag) => ({
+    pubkey: PublicKey.findProgramAddressSync(seeds.tsum(agent, tag), PROGRAM_ID)[0],
+    isSigner: false,
+    isWritable: true,
+  }));
+}
+
 export function buildEd25519Ix(
   digest32: Uint8Array,
   signer: PublicKey,
@@ -3,6 +3,8 @@ export const PROGRAM_ID = new PublicKey(
   "F3471nQ1BYRVUL2RUGRfC5JToakHkweBmLAMoMFBjo9d"
 );
 
+export const CLIENTS_PER_PAGE = 32;
+
 const leBytes = (value: bigint) => {
   const buf = Buffer.alloc(8);
   buf.writeBigUInt64LE(value);
@@ -32,12 +34,40 @@ export const seeds = {
     client.toBuffer(),
     leBytes(index),
   ],
+  clients: (agentPda: PublicKey, page: number) => {
+    const buf = Buffer.alloc(4);
+    buf.writeUInt32LE(page);
+    return [Buffer.from("clients"), agentPda.toBuffer(), buf];
+  },
+  rcfg: () => [Buffer.from("rcfg")],
+  rsum: (agentPda: PublicKey) => [Buffer.from("rsum"), agentPda.toBuffer()],
+  tsum: (agentPda: PublicKey, tag: Uint8Array) => [
+    Buffer.from("tsum"),
+    agentPda.toBuffer(),
+    Buffer.from(tag),
+  ],
+  tag: (tagHash: Uint8Array) => [Buffer.from("tag"), Buffer.from(tagHash)],
   vreq: (agentPda: PublicKey, requestHashPubkey: PublicKey) => [
     Buffer.from("vreq"),
     agentPda.toBuffer(),
     requestHashPubkey.toBuffer(),
   ],
-  vres: (requestPda: PublicKey) => [Buffer.from("vres"), requestPda.toBuffer()],
+  vres: (requestPda: PublicKey, seq: number) => {
+    const buf = Buffer.alloc(4);
+    buf.writeUInt32LE(seq);
+    return [Buffer.from("vres"), requestPda.toBuffer(), buf];
+  },
+  vsum: (agentPda: PublicKey, validator?: PublicKey) =>
+    validator
+      ? [Buffer.from("vsum"), agentPda.toBuffer(), validator.toBuffer()]
+      : [Buffer.from("vsum"), agentPda.toBuffer()],
+  vvault: (requestPda: PublicKey) => [Buffer.from("vvault"), requestPda.toBuffer()],
+  vpolicy: (agentPda: PublicKey) => [Buffer.from("vpolicy"), agentPda.toBuffer()],
+  validator: (validator: PublicKey) => [
+    Buffer.from("validator"),
+    validator.toBuffer(),
+  ],
+  attkey: (key: PublicKey) => [Buffer.from("attkey"), key.toBuffer()],
 };
 
 const wordFromU64 = (value: bigint) => {
@@ -67,3 +97,22 @@ export function encodeFeedbackAuth(auth: FeedbackAuth): Buffer {
     auth.signer.toBuffer(),
   ]);
 }
+
+export interface AttestationReport {
+  kind: number;
+  measurement: Uint8Array;
+  reportData: Uint8Array;
+  timestamp: bigint;
+}
+
+// Borsh encoding of a report, whose keccak hash the attestation key signs.
+export function encodeAttestationReport(report: AttestationReport): Buffer {
+  const timestamp = Buffer.alloc(8);
+  timestamp.writeBigInt64LE(report.timestamp);
+  return Buffer.concat([
+    Buffer.from([report.kind]),
+    Buffer.from(report.measurement),
+    Buffer.from(report.reportData),
+    timestamp,
+  ]);
+}